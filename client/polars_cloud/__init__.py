@@ -5,7 +5,7 @@ Enables users to interact with workspaces, clusters, and queries on Polars Cloud
 
 from polars_cloud import exceptions
 from polars_cloud._version import __version__
-from polars_cloud.auth import authenticate, login
+from polars_cloud.auth import authenticate, login, logout, use_profile, whoami
 from polars_cloud.config import Config
 from polars_cloud.context import (
     ClientContext,
@@ -27,6 +27,7 @@ from polars_cloud.query import (
     ParquetDst,
     ProxyQuery,
     QueryInfo,
+    QueryLimits,
     QueryProfile,
     QueryResult,
     QueryStatus,
@@ -59,6 +60,7 @@ __all__ = [
     "ParquetDst",
     "ProxyQuery",
     "QueryInfo",
+    "QueryLimits",
     "QueryProfile",
     "QueryResult",
     "QueryStatus",
@@ -70,9 +72,12 @@ __all__ = [
     "authenticate",
     "exceptions",
     "login",
+    "logout",
+    "whoami",
     "set_compute_context",
     "spawn",
     "spawn_blocking",
     "spawn_many",
     "spawn_many_blocking",
+    "use_profile",
 ]
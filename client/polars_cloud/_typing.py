@@ -21,3 +21,5 @@ ConnectionMode: TypeAlias = Literal["direct", "proxy"]
 LogLevel: TypeAlias = Literal["info", "debug", "trace"]
 FileType: TypeAlias = Literal["none", "parquet", "ipc", "csv", "ndjson", "json"]
 ScalingMode: TypeAlias = Literal["auto", "single-node", "distributed"]
+QueryPriority: TypeAlias = Literal["batch", "interactive"]
+QueryLimit: TypeAlias = Literal["timeout", "memory"]
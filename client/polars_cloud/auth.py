@@ -1,7 +1,13 @@
 from __future__ import annotations
 
+from typing import TYPE_CHECKING
+
+import polars_cloud.polars_cloud as pcr
 from polars_cloud import constants
 
+if TYPE_CHECKING:
+    from collections.abc import Callable
+
 
 def authenticate(
     *,
@@ -15,6 +21,17 @@ def authenticate(
     if no valid token is found. If the `interactive` flag is set to false
     the function will fail if no valid token was found without redirecting to `login`.
 
+    Service account secrets can also be read from the file in
+    `POLARS_CLOUD_CLIENT_SECRET_FILE`, or from a `credential_process` command of
+    the profile that prints `{"client_id": ..., "client_secret": ...}` as JSON.
+    The service account environment variables only apply to profiles that do not
+    configure a service account themselves.
+
+    In CI and on Kubernetes, an OIDC token of the workload can be used instead of
+    a service account secret. Set `POLARS_CLOUD_FEDERATED_TOKEN_FILE` to the file
+    holding the token, or `POLARS_CLOUD_FEDERATED_TOKEN` to the token itself. It is
+    exchanged for a Polars Cloud token, which is renewed automatically.
+
     Parameters
     ----------
     client_id
@@ -39,15 +56,27 @@ def authenticate(
     constants.API_CLIENT.authenticate(client_id, client_secret, interactive)
 
 
-def login() -> None:
+def login(
+    *, progress: Callable[[pcr.DeviceLoginProgress], None] | None = None
+) -> None:
     """Login interactively to Polars Cloud.
 
     This will open up a browser window where you can login
-    and grant Polars Cloud the needed access rights.
+    and grant Polars Cloud the needed access rights. When no browser
+    can be opened, for example over SSH, a URL and login code are printed
+    that can be used to log in from another device.
 
     For machine access to Polars Cloud, you must set up service accounts.
     See: https://docs.pola.rs/polars-cloud/explain/service-accounts/
 
+    Parameters
+    ----------
+    progress
+        Optional callback that is called while waiting for a login with a login
+        code, for example to show the code in a notebook widget. It receives the
+        status (`"waiting"`, `"slow_down"` or `"approved"`), the verification URL,
+        the login code and the seconds until the code expires.
+
     See Also
     --------
     authenticate: Authenticate with existing token
@@ -55,5 +84,86 @@ def login() -> None:
     Examples
     --------
     >>> pc.login()  # doctest: +SKIP
+    >>> pc.login(progress=lambda p: print(p.status, p.user_code))  # doctest: +SKIP
+    """
+    constants.API_CLIENT.login(progress=progress)
+
+
+def use_profile(name: str | None = None) -> None:
+    """Switch to a named configuration profile.
+
+    Profiles are read from the `config` file in the Polars Cloud config directory
+    and set the domain, default organization and workspace and the credential
+    source. Every profile stores its own login tokens.
+
+    Parameters
+    ----------
+    name
+        Name of the profile. Defaults to the `POLARS_CLOUD_PROFILE` environment
+        variable or the `default` profile.
+
+    Examples
+    --------
+    >>> pc.use_profile("staging")  # doctest: +SKIP
+    """
+    constants.API_CLIENT = pcr.ApiClient(profile=name)
+
+
+def _describe_credential_source(source: pcr.CredentialSource) -> str:
+    if source == pcr.CredentialSource.Environment:
+        return f"the {constants.ACCESS_TOKEN} environment variable"
+    elif source == pcr.CredentialSource.ServiceAccount:
+        return "the service account credentials"
+    elif source == pcr.CredentialSource.Federated:
+        return "the federated workload identity token"
+    else:
+        return "the stored login tokens"
+
+
+def logout() -> pcr.CredentialSource | None:
+    """Log out of Polars Cloud.
+
+    Revokes the refresh token of the current profile at the identity provider,
+    ends its session and removes the stored tokens. Credentials that are set
+    through environment variables are not affected.
+
+    Returns
+    -------
+    CredentialSource or None
+        The credential source that remains active after logging out, if any.
+
+    Examples
+    --------
+    >>> pc.logout()  # doctest: +SKIP
+    """
+    result = constants.API_CLIENT.logout()
+    profile = constants.API_CLIENT.profile.name
+    print(f"Logged out of profile {profile!r}.")
+
+    remaining = result.remaining_credential_source
+    if remaining is not None:
+        print(
+            "You are still authenticated through "
+            f"{_describe_credential_source(remaining)}."
+        )
+    return remaining
+
+
+def whoami() -> pcr.Identity:
+    """Show who you are authenticated as with Polars Cloud.
+
+    Reports the credential source, the user and its organizations, and the
+    expiry of the access token, along with the profile and domain in use. The
+    result holds no secrets, the access token is only identified by a short
+    fingerprint.
+
+    If authenticating fails, the profile, domain and credential source are still
+    reported, and `error` tells why authenticating failed.
+
+    Examples
+    --------
+    >>> identity = pc.whoami()  # doctest: +SKIP
+    >>> identity.email  # doctest: +SKIP
+    'user@example.com'
     """
-    constants.API_CLIENT.login()
+    return constants.API_CLIENT.whoami()
@@ -0,0 +1,33 @@
+from __future__ import annotations
+
+from polars_cloud import whoami
+from polars_cloud.cli.commands._utils import handle_errors
+
+
+def print_whoami() -> None:
+    """Print the identity the client is authenticated as."""
+    with handle_errors():
+        identity = whoami()
+
+    source = identity.credential_source
+    print(f"{'Profile:':<20}{identity.profile}")
+    print(f"{'Domain:':<20}{identity.domain}")
+    print(f"{'Credential source:':<20}{source if source is not None else 'unknown'}")
+    print(f"{'Subject:':<20}{identity.subject or '-'}")
+    print(f"{'User id:':<20}{identity.user_id or '-'}")
+    print(f"{'Email:':<20}{identity.email or '-'}")
+    print(f"{'Client:':<20}{identity.client_id or '-'}")
+    print(f"{'Scopes:':<20}{' '.join(identity.scopes) or '-'}")
+    if identity.expires_at is not None and identity.expires_in is not None:
+        minutes, seconds = divmod(int(identity.expires_in.total_seconds()), 60)
+        print(
+            f"{'Token expires:':<20}{identity.expires_at:%Y-%m-%d %H:%M:%S %Z} "
+            f"(in {minutes}m {seconds}s)"
+        )
+    else:
+        print(f"{'Token expires:':<20}-")
+    print(f"{'Token fingerprint:':<20}{identity.token_fingerprint or '-'}")
+    organizations = ", ".join(o.name for o in identity.organizations)
+    print(f"{'Organizations:':<20}{organizations or '-'}")
+    if identity.error is not None:
+        print(f"{'Error:':<20}{identity.error}")
@@ -69,10 +69,10 @@ def verify_workspace(
         Workspace(name=name).wait_until_active(**args)
 
 
-def delete_workspace(name: str) -> None:
+def delete_workspace(name: str, *, wait: bool = False) -> None:
     """Delete a workspace."""
     with handle_errors():
-        Workspace(name=name).delete()
+        Workspace(name=name).delete(wait=wait)
 
 
 def get_workspace_details(name: str) -> None:
@@ -5,7 +5,7 @@ import logging
 import os
 from typing import TYPE_CHECKING
 
-from polars_cloud import authenticate, constants, login
+from polars_cloud import authenticate, constants, login, logout, use_profile
 from polars_cloud.cli.commands.compute import (
     get_compute_details,
     list_compute,
@@ -21,6 +21,7 @@ from polars_cloud.cli.commands.organization import (
 from polars_cloud.cli.commands.setup import (
     setup,
 )
+from polars_cloud.cli.commands.whoami import print_whoami
 from polars_cloud.cli.commands.workspace import (
     delete_workspace,
     get_workspace_details,
@@ -39,6 +40,7 @@ def cli() -> None:
     parser = create_parser()
     args = parser.parse_args()
     _configure_logging(verbose=args.verbose)
+    _set_profile(args)
     _set_access_token(args)
     _execute_command(parser, args)
 
@@ -50,6 +52,13 @@ def _configure_logging(*, verbose: bool) -> None:
     logging.basicConfig(format=format, level=level)
 
 
+def _set_profile(args: Namespace) -> None:
+    """Switch to the configuration profile if passed."""
+    profile = getattr(args, "profile", None)
+    if profile is not None:
+        use_profile(profile)
+
+
 def _set_access_token(args: Namespace) -> None:
     """Set the access token as environment variables if passed."""
     # The login command does not use an access token
@@ -73,6 +82,12 @@ def _execute_command(parser: ArgumentParser, args: Namespace) -> None:
     elif command == "login":
         login()
 
+    elif command == "logout":
+        logout()
+
+    elif command == "whoami":
+        print_whoami()
+
     elif command == "setup":
         setup(args.organization_name, args.workspace_name)
 
@@ -104,7 +119,7 @@ def _execute_command(parser: ArgumentParser, args: Namespace) -> None:
         elif subcommand == "verify":
             verify_workspace(args.name, interval=args.interval, timeout=args.timeout)
         elif subcommand == "delete":
-            delete_workspace(args.name)
+            delete_workspace(args.name, wait=args.wait)
         elif subcommand == "details":
             get_workspace_details(args.name)
         else:  # Unreachable
@@ -0,0 +1,14 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any
+
+if TYPE_CHECKING:
+    from argparse import ArgumentParser
+
+
+def add_logout_parser(command_parsers: Any, common_parser: ArgumentParser) -> None:
+    command_parsers.add_parser(
+        "logout",
+        help="Log out of Polars Cloud and remove the stored login tokens",
+        parents=[common_parser],
+    )
@@ -1,13 +1,15 @@
 from __future__ import annotations
 
-from argparse import ArgumentParser
+from argparse import SUPPRESS, ArgumentParser
 
 from polars_cloud._version import __version__
 from polars_cloud.cli.parsers.authenticate import add_authenticate_parser
 from polars_cloud.cli.parsers.compute import add_compute_parser
 from polars_cloud.cli.parsers.login import add_login_parser
+from polars_cloud.cli.parsers.logout import add_logout_parser
 from polars_cloud.cli.parsers.organization import add_organization_parser
 from polars_cloud.cli.parsers.setup import add_setup_parser
+from polars_cloud.cli.parsers.whoami import add_whoami_parser
 from polars_cloud.cli.parsers.workspace import add_workspace_parser
 
 
@@ -21,6 +23,8 @@ def create_parser() -> ArgumentParser:
     add_authenticate_parser(subparsers, common_parser)
     add_setup_parser(subparsers, common_parser)
     add_login_parser(subparsers, common_parser)
+    add_logout_parser(subparsers, common_parser)
+    add_whoami_parser(subparsers, common_parser)
     add_organization_parser(subparsers, common_parser)
     add_workspace_parser(subparsers, common_parser)
     add_compute_parser(subparsers, common_parser)
@@ -39,6 +43,13 @@ def _create_common_parser() -> ArgumentParser:
         help="Output debug logging messages.",
         default=False,
     )
+    parser.add_argument(
+        "-p",
+        "--profile",
+        dest="profile",
+        help="Name of the configuration profile to use.",
+        default=SUPPRESS,
+    )
     return parser
 
 
@@ -0,0 +1,14 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any
+
+if TYPE_CHECKING:
+    from argparse import ArgumentParser
+
+
+def add_whoami_parser(command_parsers: Any, common_parser: ArgumentParser) -> None:
+    command_parsers.add_parser(
+        "whoami",
+        help="Show the identity you are authenticated as",
+        parents=[common_parser],
+    )
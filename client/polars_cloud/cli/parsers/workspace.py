@@ -107,6 +107,13 @@ def add_workspace_parser(command_parsers: Any, common_parser: ArgumentParser) ->
         help="The name of the workspace.",
         required=True,
     )
+    delete_parser.add_argument(
+        "-w",
+        "--wait",
+        dest="wait",
+        action="store_true",
+        help="Wait until the workspace stack has been removed.",
+    )
 
     # Workspace - details
     details_parser = subparsers.add_parser(
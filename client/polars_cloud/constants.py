@@ -1,7 +1,5 @@
 """Authentication constants."""
 
-import os
-
 import polars_cloud.polars_cloud as pcr
 
 CLIENT_ID = "POLARS_CLOUD_CLIENT_ID"
@@ -9,9 +7,6 @@ CLIENT_SECRET = "POLARS_CLOUD_CLIENT_SECRET"
 ACCESS_TOKEN = "POLARS_CLOUD_ACCESS_TOKEN"
 ACCESS_TOKEN_PATH = "POLARS_CLOUD_CONFIG_DIR"
 
-POLARS_CLOUD_DOMAIN = os.getenv("POLARS_CLOUD_DOMAIN", "prd.cloud.pola.rs")
-AUTH_DOMAIN = f"auth.{POLARS_CLOUD_DOMAIN}"
-
 LOGIN_CLIENT_ID = "PolarsCloud"
 LOGIN_AUDIENCE = "account"
 
@@ -1,6 +1,5 @@
 from __future__ import annotations
 
-import datetime
 import logging
 import time
 from contextlib import ContextDecorator
@@ -54,9 +53,6 @@ class ClientContext:
     def _get_direct_client(self) -> pcr.SchedulerClient | None:
         return self._direct_client
 
-    def _get_token(self) -> str | None:
-        return None
-
 
 class ClusterContext(ClientContext):
     """Cluster context in which queries are executed.
@@ -76,6 +72,8 @@ class ClusterContext(ClientContext):
         public_server_crt: bytes | None = None,
         tls_certificate: bytes | None = None,
         tls_private_key: bytes | None = None,
+        compression: pcr.GrpcCompression | None = None,
+        compression_threshold: int | None = None,
     ) -> None:
         self._connection_mode = pcr.DBClusterModeSchema.Direct
         self._compute_id = uuid4()
@@ -86,6 +84,8 @@ class ClusterContext(ClientContext):
         client_options.tls_certificate = tls_certificate
         client_options.tls_private_key = tls_private_key
         client_options.insecure = insecure
+        client_options.compression = compression
+        client_options.compression_threshold = compression_threshold
         compute_address = f"{compute_address}:{compute_port}"
         self._direct_client = pcr.SchedulerClient(compute_address, client_options)
 
@@ -225,7 +225,6 @@ class ComputeContext(ClientContext, ContextDecorator):
         self._insecure = insecure
         self._direct_client = None
         self._compute_id: UUID | None = None
-        self._compute_token: str | None = None
         self._requirements_txt: str | None
         self._name: str | None = None
 
@@ -630,42 +629,20 @@ class ComputeContext(ClientContext, ContextDecorator):
         if self._direct_client is not None:
             return self._direct_client
 
-        logger.debug("Getting compute server info")
+        logger.debug("Connecting to compute cluster")
         assert self._compute_id is not None, (
             "Compute id undefined while getting direct client"
         )
-        server_info = constants.API_CLIENT.get_compute_server_info(
-            self.workspace.id, self._compute_id
-        )
-        logger.debug("Successfully obtained compute server info")
-
         client_options = ClientOptions()
-        client_options.public_server_crt = str.encode(server_info.public_server_key)
         client_options.insecure = self._insecure
-        compute_address = f"{server_info.public_address}:{DEFAULT_SCHEDULER_PORT}"
-        client = pcr.SchedulerClient(compute_address, client_options)
+        # The client fetches the compute token and refreshes it before it expires.
+        client = pcr.SchedulerClient.for_compute(
+            constants.API_CLIENT, self.workspace.id, self._compute_id, client_options
+        )
+        logger.debug("Successfully connected to compute cluster")
         self._direct_client = client
         return self._direct_client
 
-    def _get_token(self) -> str | None:
-        assert self._compute_id is not None, "Compute id undefined while getting token"
-
-        if self.connection_mode != "direct":
-            return None
-
-        if self._direct_client is None:
-            self._get_direct_client()
-
-        if self._compute_token is None or pcr.py_is_token_expired(
-            self._compute_token, datetime.timedelta(minutes=5)
-        ):
-            response = constants.API_CLIENT.get_compute_cluster_token(
-                self.workspace.id, self._compute_id
-            )
-            self._compute_token = response.token
-
-        return self._compute_token
-
     @property
     def polars_version(self) -> str:
         return self._polars_version
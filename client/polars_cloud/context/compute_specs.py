@@ -80,6 +80,9 @@ def resolve_compute_context_specs(
             memory = defaults.memory
             cpus = defaults.cpus
             instance_type = defaults.instance_type
+            if big_instance_type is None and big_instance_multiplier is None:
+                big_instance_type = defaults.big_instance_type
+                big_instance_multiplier = defaults.big_instance_multiplier
 
     storage_resolved = storage or getattr(workspace.defaults, "storage", None)
     cluster_size_resolved: int = cluster_size or getattr(
@@ -105,7 +105,13 @@ class Organization:
         elif isinstance(organization, UUID):
             return cls(id=organization)
         elif organization is None:
-            return cls()
+            default = constants.API_CLIENT.profile.organization
+            if default is None:
+                return cls()
+            try:
+                return cls(id=UUID(default))
+            except ValueError:
+                return cls(name=default)
         else:
             msg = f"Unknown type {type(organization)}, expected str | Organization | UUID | None"
             raise RuntimeError(msg)
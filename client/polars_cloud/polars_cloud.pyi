@@ -1,10 +1,11 @@
+from collections.abc import Callable
 from datetime import datetime, timedelta
 from enum import Enum
 from typing import Any
 from uuid import UUID
 
-from polars_cloud._typing import ConnectionMode, FileType, LogLevel
-from polars_cloud.query.query import DistributionSettings
+from polars_cloud._typing import ConnectionMode, FileType, LogLevel, QueryLimit
+from polars_cloud.query.query import DistributionSettings, QueryLimits
 
 def serialize_query_settings(
     *,
@@ -14,6 +15,7 @@ def serialize_query_settings(
     shuffle_opts: PyShuffleOpts = ...,
     n_retries: int = ...,
     distributed_settings: DistributionSettings | None = ...,
+    limits: QueryLimits | None = ...,
 ) -> PyQuerySettings: ...
 def py_is_token_expired(
     token: str, reject_tokens_expiring_in_less_than: timedelta | None
@@ -100,12 +102,18 @@ class DefaultComputeSpecs:
     instance_type: str | None
     """The type of instance (e.g., t3.micro)."""
 
+    big_instance_type: str | None
+    """The type of instance of the big node."""
+
     cpus: int | None
     """Number of CPUs."""
 
     ram_gb: int | None
     """Amount of RAM in GiB."""
 
+    big_instance_multiplier: int | None
+    """Resource multiplier of the big node."""
+
     storage: int | None
     """Amount of disk storage in GiB."""
 
@@ -167,6 +175,15 @@ class QueryWithStatusSchema:
     status: StatusSchema
     """Current status of the query"""
 
+class QueryCountSchema:
+    timestamp: datetime
+    """Start of the time bucket"""
+    count: int
+    """Number of queries in the bucket"""
+    count_successful: int
+    count_failed: int
+    count_in_progress: int
+
 class QueryStateTimingSchema:
     latest_status: QueryStatusCodeSchema
     """Last known status for query"""
@@ -472,6 +489,8 @@ class QueryInfoPy:
     phys_plan_explain: str | None
     phys_plan_dot: str | None
     stages_stats: Any | None
+    limits_hit: list[QueryLimit]
+    """The limits of the query settings that the query hit."""
 
 class ClientOptions:
     tls_cert_domain: str | None
@@ -479,6 +498,21 @@ class ClientOptions:
     tls_certificate: bytes | None
     tls_private_key: bytes | None
     insecure: bool
+    compression: GrpcCompression | None
+    """Compression of large messages, `None` to send them uncompressed."""
+
+    compression_threshold: int | None
+    """Messages smaller than this many bytes are sent uncompressed."""
+
+class GrpcCompression(Enum):
+    """Compression of the gRPC messages sent to the control and compute plane.
+
+    If the server does not accept the encoding, the message is sent again with
+    an encoding it does accept, or uncompressed, and that is used from then on.
+    """
+
+    Zstd: int
+    Gzip: int
 
 class QueryProfilePy:
     tag: bytes
@@ -517,15 +551,144 @@ class OrganizationSchema:
     deleted_at: datetime | None
     """Timestamp of the last deletion."""
 
+class CredentialSource(Enum):
+    """Where the credentials of a profile are taken from."""
+
+    Environment: int
+    ServiceAccount: int
+    Login: int
+    Federated: int
+
+class Profile:
+    """A named set of settings from the Polars Cloud config file."""
+
+    name: str
+    domain: str
+    api_domain_prefix: str
+    organization: str | None
+    """Default organization, by name or id."""
+
+    workspace: str | None
+    """Default workspace, by name or id."""
+
+    credential_source: CredentialSource | None
+    client_id: str | None
+    client_secret_file: str | None
+    """File holding the client secret of the service account."""
+
+    credential_process: str | None
+    """Command that prints the service account credentials as JSON."""
+
+    federated_token_file: str | None
+    """File holding the external OIDC token for workload identity federation."""
+
+    federated_token_issuer: str | None
+    """Alias of the identity provider that issued the external OIDC token."""
+
+    grpc_compression: GrpcCompression | None
+    """Compression of the gRPC messages sent to the control plane."""
+
+    grpc_compression_threshold: int | None
+    """Messages smaller than this many bytes are sent uncompressed."""
+
+class DeviceLoginProgress:
+    """Progress of a device login, passed to the progress callback of `login`."""
+
+    status: str
+    """One of `waiting`, `slow_down` or `approved`."""
+
+    verification_uri: str
+    user_code: str
+    expires_in_secs: int
+    """Seconds until the login code expires."""
+
+class LogoutResult:
+    """Outcome of a logout."""
+
+    revoked: bool
+    """Whether a stored refresh token was revoked at the auth server."""
+
+    remaining_credential_source: CredentialSource | None
+    """Credential source that is still used to authenticate after logging out."""
+
+class Identity:
+    """Who the client is authenticated as, without any secrets.
+
+    If authenticating fails, only the settings of the profile are filled in and
+    `error` tells why.
+    """
+
+    profile: str
+    """Name of the configuration profile."""
+
+    domain: str
+    """Polars Cloud domain of the profile."""
+
+    credential_source: CredentialSource | None
+    subject: str | None
+    """Subject of the access token, the id of the user or service account."""
+
+    user_id: UUID | None
+    email: str | None
+    client_id: str | None
+    """Client the access token was issued to."""
+
+    scopes: list[str]
+    """Scopes granted to the access token."""
+
+    organizations: list[OrganizationSchema]
+    expires_at: datetime | None
+    expires_in: timedelta | None
+    """Remaining lifetime of the access token."""
+
+    token_fingerprint: str | None
+    """Short SHA-256 hash of the access token."""
+
+    error: str | None
+    """Why authenticating failed, `None` if it succeeded."""
+
+class TokenClaims:
+    """The claims of a validated access token."""
+
+    sub: str | None
+    """Subject, the id of the user or service account."""
+
+    iss: str
+    """Issuer, the realm of the auth server."""
+
+    aud: list[str]
+    azp: str | None
+    """Client the token was issued to."""
+
+    exp: datetime
+    nbf: datetime | None
+    iat: datetime | None
+    email: str | None
+    preferred_username: str | None
+    scope: list[str]
+    """Scopes granted to the token."""
+
 class ApiClient:
+    def __init__(
+        self,
+        profile: str | None = None,
+        *,
+        token_refresh_skew: timedelta | None = None,
+    ) -> None: ...
+    @property
+    def profile(self) -> Profile: ...
     def authenticate(
         self,
         client_id: str | None = None,
         client_secret: str | None = None,
         interactive: bool = True,
     ) -> None: ...
-    def login(self) -> None: ...
+    def login(
+        self, *, progress: Callable[[DeviceLoginProgress], None] | None = None
+    ) -> None: ...
+    def logout(self) -> LogoutResult: ...
     def clear_authentication(self) -> None: ...
+    def get_token_claims(self) -> TokenClaims: ...
     def get_auth_header(self) -> str: ...
 
     # Workspace methods
@@ -536,7 +699,36 @@ class ApiClient:
         self, workspace_id: UUID
     ) -> WorkspaceSetupUrlSchema: ...
     def delete_workspace(self, workspace_id: UUID) -> DeleteWorkspaceSchema | None: ...
+    def wait_for_workspace_state(
+        self,
+        workspace_id: UUID,
+        state: WorkspaceStateSchema,
+        *,
+        interval_secs: int = 2,
+        timeout_secs: int = 300,
+    ) -> WorkspaceSchema | None: ...
     def get_workspace(self, workspace_id: UUID) -> WorkspaceSchema: ...
+    def patch_workspace_details(
+        self,
+        workspace_id: UUID,
+        *,
+        name: str | None = None,
+        description: str | None = None,
+        idle_timeout_mins: int | None = None,
+    ) -> None: ...
+    def set_workspace_cluster_defaults(
+        self,
+        workspace_id: UUID,
+        *,
+        cluster_size: int,
+        instance_type: str | None = None,
+        big_instance_type: str | None = None,
+        cpus: int | None = None,
+        ram_gb: int | None = None,
+        big_instance_multiplier: int | None = None,
+        storage: int | None = None,
+    ) -> None: ...
+    def delete_workspace_cluster_defaults(self, workspace_id: UUID) -> None: ...
     def get_workspaces(
         self, name: str | None = None, organization_id: UUID | None = None
     ) -> list[WorkspaceSchema]: ...
@@ -617,10 +809,48 @@ class ApiClient:
         self, workspace_id: UUID, query_id: UUID
     ) -> QueryPlansSchema: ...
     def cancel_proxy_query(self, workspace_id: UUID, query_id: UUID) -> None: ...
-    def get_queries(self, workspace_id: UUID) -> list[QueryWithStateTimingSchema]: ...
+    def get_queries(
+        self,
+        workspace_id: UUID,
+        *,
+        cluster_id: UUID | None = None,
+        user_id: UUID | None = None,
+        status: list[QueryStatusCodeSchema] | None = None,
+        label: str | None = None,
+        requested_after: datetime | None = None,
+        requested_before: datetime | None = None,
+        ended_after: datetime | None = None,
+        ended_before: datetime | None = None,
+        min_duration_secs: int | None = None,
+    ) -> list[QueryWithStateTimingSchema]: ...
+    def get_queries_ipc(
+        self,
+        workspace_id: UUID,
+        *,
+        cluster_id: UUID | None = None,
+        user_id: UUID | None = None,
+        status: list[QueryStatusCodeSchema] | None = None,
+        label: str | None = None,
+        requested_after: datetime | None = None,
+        requested_before: datetime | None = None,
+        ended_after: datetime | None = None,
+        ended_before: datetime | None = None,
+        min_duration_secs: int | None = None,
+        include_results: bool = False,
+    ) -> bytes: ...
+    def get_query_count(
+        self,
+        workspace_id: UUID,
+        start: datetime,
+        end: datetime,
+        interval: timedelta,
+        *,
+        cluster_id: UUID | None = None,
+    ) -> list[QueryCountSchema]: ...
 
     # User methods
     def get_user(self) -> UserSchema: ...
+    def whoami(self) -> Identity: ...
     def get_query_result(self, query_id: UUID) -> QueryInfoPy: ...
     def submit_query(
         self,
@@ -641,6 +871,20 @@ class ApiClient:
 
 class SchedulerClient:
     def __init__(self, compute_addr: str, client_options: ClientOptions): ...
+    @staticmethod
+    def for_compute(
+        client: ApiClient,
+        workspace_id: UUID,
+        compute_id: UUID,
+        client_options: ClientOptions | None = None,
+    ) -> SchedulerClient:
+        """Connect to the scheduler of a direct-mode cluster of the workspace.
+
+        The address and certificate of the cluster are looked up with `client`,
+        which also fetches the compute token. The token is cached and refreshed
+        before it expires, so the methods need no `token`.
+        """
+
     def cancel_direct_query(self, query_id: UUID, token: str | None) -> None: ...
     def get_direct_query_status(
         self, query_id: UUID, token: str | None
@@ -648,6 +892,16 @@ class SchedulerClient:
     def get_direct_query_result(
         self, query_id: UUID, token: str | None
     ) -> QueryInfoPy: ...
+    def fetch_direct_query_result(
+        self,
+        query_id: UUID,
+        token: str | None,
+        start_chunk: int = 0,
+        max_chunk_bytes: int | None = None,
+    ) -> ResultChunkIterator: ...
+    def watch_direct_query(
+        self, query_id: UUID, token: str | None
+    ) -> QueryWatchIterator: ...
     def do_query(
         self,
         plan: bytes,
@@ -662,6 +916,50 @@ class SchedulerClient:
         self, query_id: UUID, token: str | None, phys: bool = False, ir: bool = False
     ) -> QueryPlansPy: ...
 
+class QueryHandle:
+    """A submitted query, independent of the mode of the cluster it runs on."""
+
+    @staticmethod
+    def proxy(client: ApiClient, workspace_id: UUID, query_id: UUID) -> QueryHandle:
+        """Handle of a query submitted through the control plane."""
+
+    @staticmethod
+    def direct(
+        client: SchedulerClient,
+        query_id: UUID,
+        token: Callable[[], str | None] | None = None,
+    ) -> QueryHandle:
+        """Handle of a query submitted directly to a cluster.
+
+        `token` is called before every request for the current compute token.
+        """
+
+    @property
+    def query_id(self) -> UUID: ...
+    @property
+    def mode(self) -> DBClusterModeSchema: ...
+    def status(self) -> QueryStatusCodeSchema: ...
+    def wait(self, timeout: timedelta | None = None) -> QueryStatusCodeSchema:
+        """Poll the status until the query is done and return the final status."""
+
+    def result(self, timeout: timedelta | None = None) -> QueryInfoPy:
+        """Wait for the query to be done and return its result."""
+
+    def cancel(self) -> None: ...
+    def plans(self, phys: bool = False, ir: bool = False) -> QueryPlansPy:
+        """Plans of the query.
+
+        In proxy mode the plans come from the control plane, which only keeps
+        them as dot graphs.
+        """
+
+    def profile(self, tag: bytes | None = None) -> QueryProfilePy | None:
+        """Profile of the query, `None` if it did not change since `tag`.
+
+        Only available in direct mode, profiles are streamed by the scheduler of
+        the cluster and the control plane does not keep them.
+        """
+
 class PlanFormatPy(Enum):
     Dot: int
     Explain: int
@@ -670,3 +968,31 @@ class QueryPlansPy:
     format: PlanFormatPy
     ir_plan: str | None
     phys_plan: str | None
+
+class QueryWatchEventPy:
+    """A status transition or stage progress of a watched query."""
+
+    status: QueryStatusCodeSchema
+    total_stages: int
+    finished_stages: int
+    failed_stages: int
+
+class QueryWatchIterator:
+    def __iter__(self) -> QueryWatchIterator: ...
+    def __next__(self) -> QueryWatchEventPy: ...
+
+class ResultChunkPy:
+    """A chunk of a query result, holding a complete Arrow IPC stream."""
+
+    index: int
+    total_chunks: int
+    num_rows: int
+    data: bytes
+
+class ResultChunkIterator:
+    next_chunk: int
+    """Index of the next chunk, to resume the download from after an error."""
+
+    received_bytes: int
+    def __iter__(self) -> ResultChunkIterator: ...
+    def __next__(self) -> ResultChunkPy: ...
@@ -3,6 +3,7 @@ from polars_cloud.query.ext import ExecuteRemote, LazyFrameRemote
 from polars_cloud.query.query import (
     DirectQuery,
     ProxyQuery,
+    QueryLimits,
     spawn,
     spawn_blocking,
     spawn_many,
@@ -22,6 +23,7 @@ __all__ = [
     "ParquetDst",
     "ProxyQuery",
     "QueryInfo",
+    "QueryLimits",
     "QueryProfile",
     "QueryResult",
     "QueryStatus",
@@ -26,7 +26,7 @@ if TYPE_CHECKING:
     )
     from polars_cloud.polars_cloud import PyQuerySettings
     from polars_cloud.query.dst import Dst
-    from polars_cloud.query.query import DistributionSettings
+    from polars_cloud.query.query import DistributionSettings, QueryLimits
 
 
 def prepare_query(
@@ -40,6 +40,7 @@ def prepare_query(
     shuffle_compression_level: int | None = None,
     distributed_settings: DistributionSettings | None,
     n_retries: int,
+    limits: QueryLimits | None = None,
     sink_to_single_file: bool | None = None,
     optimizations: QueryOptFlags,
 ) -> tuple[bytes, PyQuerySettings]:
@@ -189,6 +190,7 @@ If you want to:
         shuffle_opts=shuffle_opts,
         n_retries=n_retries,
         distributed_settings=distributed_settings,
+        limits=limits,
     )
 
     return plan, settings
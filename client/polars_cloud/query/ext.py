@@ -8,10 +8,11 @@ from polars.lazyframe.opt_flags import DEFAULT_QUERY_OPT_FLAGS
 
 from polars_cloud import config as pc_cfg
 from polars_cloud.query.dst import CsvDst, IpcDst, ParquetDst, TmpDst
-from polars_cloud.query.query import DistributionSettings, spawn
+from polars_cloud.query.query import DistributionSettings, QueryLimits, spawn
 
 if TYPE_CHECKING:
     from collections.abc import Mapping, Sequence
+    from datetime import timedelta
     from typing import Literal
 
     from polars import DataFrame, QueryOptFlags
@@ -29,6 +30,7 @@ if TYPE_CHECKING:
     from polars_cloud._typing import (
         Engine,
         PlanTypePreference,
+        QueryPriority,
         ScalingMode,
         ShuffleCompression,
         ShuffleFormat,
@@ -57,6 +59,7 @@ class LazyFrameRemote:
         self._engine: Engine = engine
         self._labels: None | list[str] = None
         self._n_retries = n_retries
+        self._limits: QueryLimits | None = None
         self.plan_type: PlanTypePreference = plan_type
         self.scaling_mode = scaling_mode
 
@@ -111,6 +114,7 @@ class LazyFrameRemote:
             context=self.context,
             plan_type=self.plan_type,
             n_retries=self._n_retries,
+            limits=self._limits,
             labels=self._labels,
             engine=self._engine,
             distributed_settings=distributed_settings,
@@ -128,6 +132,7 @@ class LazyFrameRemote:
             context=self.context,
             plan_type=self.plan_type,
             n_retries=self._n_retries,
+            limits=self._limits,
             labels=self._labels,
             engine=self._engine,
         )
@@ -147,6 +152,41 @@ class LazyFrameRemote:
         self._labels = [labels] if isinstance(labels, str) else labels
         return self
 
+    def limits(
+        self,
+        limits: QueryLimits | None = None,
+        *,
+        timeout: timedelta | None = None,
+        priority: QueryPriority = "batch",
+        memory_limit: int | None = None,
+    ) -> LazyFrameRemote:
+        """Limit the execution of the query.
+
+        Parameters
+        ----------
+        limits
+            The limits of the query. If not given, they are built from the
+            other arguments.
+        timeout
+            Wall-clock time after which the query is canceled.
+        priority : {'batch', 'interactive'}
+            Priority class of the query. Interactive queries are scheduled ahead of
+            batch queries.
+        memory_limit
+            Soft limit in bytes on the memory of the query, above which it spills
+            to disk.
+
+        Examples
+        --------
+        >>> query.remote(ctx).limits(timeout=timedelta(minutes=5)).sink_parquet(...)
+        """
+        if limits is None:
+            limits = QueryLimits(
+                timeout=timeout, priority=priority, memory_limit=memory_limit
+            )
+        self._limits = limits
+        return self
+
     def _scaling_mode(self) -> ExecuteRemote:
         # Global overwrite
         if pc_cfg.Config._is_set(pc_cfg._SINGLE_NODE, "1"):
@@ -592,12 +632,14 @@ class ExecuteRemote:
         shuffle_format: ShuffleFormat = "auto",
         shuffle_compression_level: int | None = None,
         distributed_settings: DistributionSettings | None = None,
+        limits: QueryLimits | None = None,
     ) -> None:
         self.lf: pl.LazyFrame = lf
         self.context: ComputeContext | None = context
         self._engine: Engine = engine
         self._labels: None | list[str] = labels
         self._n_retries = n_retries
+        self._limits = limits
         self.plan_type: PlanTypePreference = plan_type
         # Optimizations settings for distributed
         self._shuffle_compression: ShuffleCompression = shuffle_compression
@@ -630,6 +672,7 @@ class ExecuteRemote:
             shuffle_format=self._shuffle_format,
             shuffle_compression_level=self._shuffle_compression_level,
             n_retries=self._n_retries,
+            limits=self._limits,
             distributed=self._distributed_settings,
             optimizations=pl.QueryOptFlags(),
         )
@@ -844,6 +887,7 @@ class ExecuteRemote:
             shuffle_format=self._shuffle_format,
             shuffle_compression_level=self._shuffle_compression_level,
             n_retries=self._n_retries,
+            limits=self._limits,
             distributed=self._distributed_settings,
             optimizations=optimizations,
         )
@@ -1020,6 +1064,7 @@ class ExecuteRemote:
             shuffle_format=self._shuffle_format,
             shuffle_compression_level=self._shuffle_compression_level,
             n_retries=self._n_retries,
+            limits=self._limits,
             distributed=self._distributed_settings,
             sink_to_single_file=sink_to_single_file,
             optimizations=optimizations,
@@ -1125,6 +1170,7 @@ class ExecuteRemote:
             shuffle_format=self._shuffle_format,
             shuffle_compression_level=self._shuffle_compression_level,
             n_retries=self._n_retries,
+            limits=self._limits,
             distributed=self._distributed_settings,
             sink_to_single_file=sink_to_single_file,
             optimizations=optimizations,
@@ -43,6 +43,8 @@ from polars_cloud.query.query_status import QueryStatus
 logger = logging.getLogger(__name__)
 
 if TYPE_CHECKING:
+    from collections.abc import Callable, Iterator
+    from datetime import timedelta
     from pathlib import Path
     from uuid import UUID
 
@@ -52,6 +54,7 @@ if TYPE_CHECKING:
         Engine,
         PlanType,
         PlanTypePreference,
+        QueryPriority,
         ShuffleCompression,
         ShuffleFormat,
     )
@@ -136,11 +139,12 @@ class ProxyQuery(InProgressQueryRemote):
     def __init__(self, query_id: UUID, workspace_id: UUID):
         self._query_id = query_id
         self._workspace_id = workspace_id
+        self._handle = pcr.QueryHandle.proxy(
+            constants.API_CLIENT, workspace_id, query_id
+        )
 
     def get_status(self) -> QueryStatus:
-        schema = constants.API_CLIENT.get_query(self._workspace_id, self._query_id)
-        query_status = QueryStatus._from_api_schema(schema.state_timing.latest_status)
-        return query_status
+        return QueryStatus._from_api_schema(self._handle.status())
 
     def _get_result(
         self, status: QueryStatus, *, raise_on_failure: bool = True
@@ -165,9 +169,7 @@ class ProxyQuery(InProgressQueryRemote):
         return self._get_result(status, raise_on_failure=raise_on_failure)
 
     def cancel(self) -> None:
-        constants.API_CLIENT.cancel_proxy_query(
-            workspace_id=self._workspace_id, query_id=self._query_id
-        )
+        self._handle.cancel()
 
 
 @dataclass
@@ -177,6 +179,31 @@ class DistributionSettings:
     equi_join_broadcast_limit: int = 256 * 1024**2
 
 
+@dataclass
+class QueryLimits:
+    """Limits on the execution of a query.
+
+    Parameters
+    ----------
+    timeout
+        Wall-clock time after which the query is canceled.
+    priority : {'batch', 'interactive'}
+        Priority class of the query. Interactive queries are scheduled ahead of
+        batch queries.
+    memory_limit
+        Soft limit in bytes on the memory of the query, above which it spills to disk.
+
+    Examples
+    --------
+    >>> limits = pc.QueryLimits(timeout=timedelta(minutes=10), priority="interactive")
+    >>> query.remote(ctx).limits(limits).sink_parquet(...)
+    """
+
+    timeout: timedelta | None = None
+    priority: QueryPriority = "batch"
+    memory_limit: int | None = None
+
+
 class DirectQuery(InProgressQueryRemote):
     """A Polars Cloud direct connect query.
 
@@ -202,12 +229,56 @@ class DirectQuery(InProgressQueryRemote):
         self._client = client
         self._cluster = cluster
         self._tag: bytes | None = None
+        self._handle = pcr.QueryHandle.direct(client, query_id)
 
     def get_status(self) -> QueryStatus:
-        status_code = self._client.get_direct_query_status(
-            self._query_id, token=self._cluster._get_token()
+        return QueryStatus._from_api_schema(self._handle.status())
+
+    def watch(self) -> Iterator[pcr.QueryWatchEventPy]:
+        """Iterate over the status transitions and stage progress of the query.
+
+        The cluster pushes an event whenever the status or the number of
+        finished or failed stages changes, so no polling is needed. The iterator
+        ends once the query is done.
+
+        Examples
+        --------
+        >>> for event in query.watch():  # doctest: +SKIP
+        ...     print(event.status, event.finished_stages, event.total_stages)
+        """
+        return self._client.watch_direct_query(self._query_id, token=None)
+
+    def fetch_result(
+        self,
+        *,
+        start_chunk: int = 0,
+        progress: Callable[[int, int], None] | None = None,
+    ) -> Iterator[pl.DataFrame]:
+        """Stream the in-memory result of the query in chunks.
+
+        Chunks are downloaded as they are consumed, so the result is never held
+        in a single message. Every chunk is decoded into its own DataFrame.
+
+        Parameters
+        ----------
+        start_chunk
+            Index of the first chunk to download. Pass the number of chunks
+            that were already received to resume an interrupted download.
+        progress
+            Optional callback that receives the number of chunks received so
+            far and the total number of chunks.
+
+        Examples
+        --------
+        >>> df = pl.concat(query.fetch_result())  # doctest: +SKIP
+        """
+        chunks = self._client.fetch_direct_query_result(
+            self._query_id, token=None, start_chunk=start_chunk
         )
-        return QueryStatus._from_api_schema(status_code)
+        for chunk in chunks:
+            if progress is not None:
+                progress(chunk.index + 1, chunk.total_chunks)
+            yield pl.read_ipc_stream(chunk.data)
 
     def get_profile(self) -> QueryProfile | None:
         """Get the current profile of the query if available."""
@@ -215,9 +286,7 @@ class DirectQuery(InProgressQueryRemote):
         return self._get_profile()
 
     def _get_profile(self) -> QueryProfile | None:
-        profile_py = self._client.get_direct_query_profile(
-            self._query_id, self._tag, token=self._cluster._get_token()
-        )
+        profile_py = self._handle.profile(self._tag)
 
         if profile_py is None:
             return None
@@ -230,9 +299,7 @@ class DirectQuery(InProgressQueryRemote):
     def _get_result(
         self, status: QueryStatus, *, raise_on_failure: bool = True
     ) -> QueryResult:
-        query_info_py = self._client.get_direct_query_result(
-            self._query_id, token=self._cluster._get_token()
-        )
+        query_info_py = self._client.get_direct_query_result(self._query_id, token=None)
         query_info = QueryInfo(self._query_id, query_info_py)
         result = QueryResult(result=query_info, status=status, query=self)
 
@@ -258,9 +325,7 @@ class DirectQuery(InProgressQueryRemote):
         return self._poll_profile_until_update()
 
     def cancel(self) -> None:
-        self._client.cancel_direct_query(
-            self._query_id, token=self._cluster._get_token()
-        )
+        self._handle.cancel()
 
     async def _poll_profile_until_update_async(self) -> QueryProfile:
         """Poll the profile of the query until there is an update."""
@@ -314,17 +379,13 @@ class DirectQuery(InProgressQueryRemote):
             Passed to matplotlib if `show == True`.
         """
         if plan_type == "ir":
-            plans = self._client.get_direct_query_plan(
-                query_id=self._query_id, token=self._cluster._get_token(), ir=True
-            )
+            plans = self._handle.plans(ir=True)
             if plans.format != PlanFormatPy.Dot or plans.ir_plan is None:
                 msg = "no dot diagram created for this query.\n\nConsider setting 'plan_type' to 'dot'"
                 raise NoDataError(msg)
             dot = plans.ir_plan
         elif plan_type == "physical":
-            plans = self._client.get_direct_query_plan(
-                query_id=self._query_id, token=self._cluster._get_token(), phys=True
-            )
+            plans = self._handle.plans(phys=True)
             if plans.format != PlanFormatPy.Dot or plans.phys_plan is None:
                 msg = "no dot diagram created for this query.\n\nConsider setting 'plan_type' to 'dot'. If the query wasn't distributed, no physical plan was created."
                 raise NoDataError(msg)
@@ -371,16 +432,12 @@ class DirectQuery(InProgressQueryRemote):
 
         """
         if plan_type == "physical":
-            plans = self._client.get_direct_query_plan(
-                query_id=self._query_id, token=self._cluster._get_token(), phys=True
-            )
+            plans = self._handle.plans(phys=True)
             if plans.format != PlanFormatPy.Explain:
                 return ""
             return plans.phys_plan or ""
         elif plan_type == "ir":
-            plans = self._client.get_direct_query_plan(
-                query_id=self._query_id, token=self._cluster._get_token(), ir=True
-            )
+            plans = self._handle.plans(ir=True)
             if plans.format != PlanFormatPy.Explain:
                 return ""
             return plans.ir_plan or ""
@@ -401,6 +458,7 @@ def spawn_many(
     shuffle_format: ShuffleFormat = "auto",
     distributed: DistributionSettings | None | bool = None,
     n_retries: int = 0,
+    limits: QueryLimits | None = None,
     **optimizations: bool,
 ) -> list[ProxyQuery] | list[DirectQuery]:
     """Spawn multiple remote queries and await them asynchronously.
@@ -443,6 +501,8 @@ def spawn_many(
         and available machines.
     n_retries
         How often failed tasks should be retried.
+    limits
+        Limits on the execution of the query, such as a timeout.
     **optimizations
         Optimizations to enable or disable in the query optimizer, e.g.
         `projection_pushdown=False`.
@@ -467,6 +527,7 @@ def spawn_many(
             shuffle_compression=shuffle_compression,
             shuffle_format=shuffle_format,
             n_retries=n_retries,
+            limits=limits,
             distributed=distributed,
             **optimizations,  # type: ignore[arg-type]
         )
@@ -486,6 +547,7 @@ def spawn_many_blocking(
     shuffle_format: ShuffleFormat = "auto",
     distributed: DistributionSettings | None | bool = None,
     n_retries: int = 0,
+    limits: QueryLimits | None = None,
     **optimizations: bool,
 ) -> list[QueryResult]:
     """Spawn multiple remote queries and await them while blocking the thread.
@@ -528,6 +590,8 @@ def spawn_many_blocking(
         and available machines.
     n_retries
         How often failed tasks should be retried.
+    limits
+        Limits on the execution of the query, such as a timeout.
     **optimizations
         Optimizations to enable or disable in the query optimizer, e.g.
         `projection_pushdown=False`.
@@ -553,6 +617,7 @@ def spawn_many_blocking(
             shuffle_compression=shuffle_compression,
             shuffle_format=shuffle_format,
             n_retries=n_retries,
+            limits=limits,
             distributed=distributed,
             **optimizations,
         )
@@ -575,6 +640,7 @@ def spawn(
     shuffle_compression_level: int | None = None,
     distributed: DistributionSettings | None | bool = None,
     n_retries: int = 0,
+    limits: QueryLimits | None = None,
     sink_to_single_file: bool | None = None,
     optimizations: pl.QueryOptFlags = DEFAULT_QUERY_OPT_FLAGS,
 ) -> ProxyQuery | DirectQuery:
@@ -620,6 +686,8 @@ def spawn(
         and available machines.
     n_retries
         How often failed tasks should be retried.
+    limits
+        Limits on the execution of the query, such as a timeout.
     sink_to_single_file
         Perform the sink into a single file.
 
@@ -678,6 +746,7 @@ def spawn(
         shuffle_format=shuffle_format,
         shuffle_compression_level=shuffle_compression_level,
         n_retries=n_retries,
+        limits=limits,
         distributed_settings=distributed,
         sink_to_single_file=sink_to_single_file,
         optimizations=optimizations,
@@ -687,12 +756,8 @@ def spawn(
         isinstance(context, ComputeContext) and context.connection_mode == "direct"
     ):
         client: pcr.SchedulerClient = context._get_direct_client()  # type: ignore[assignment]
-        if isinstance(context, ComputeContext):
-            token = context._get_token()
-        else:
-            token = None
         try:
-            q_id = client.do_query(plan=plan, settings=settings, token=token)
+            q_id = client.do_query(plan=plan, settings=settings, token=None)
         except pcr.EncodedPolarsError as e:
             raise decode_error(str(e)) from None
 
@@ -725,6 +790,7 @@ def spawn_blocking(
     shuffle_compression: ShuffleCompression = "auto",
     distributed: DistributionSettings | None | bool = None,
     n_retries: int = 0,
+    limits: QueryLimits | None = None,
     sink_to_single_file: bool | None = None,
     optimizations: pl.QueryOptFlags = DEFAULT_QUERY_OPT_FLAGS,
 ) -> QueryResult:
@@ -766,6 +832,8 @@ def spawn_blocking(
         and available machines.
     n_retries
         How often failed tasks should be retried.
+    limits
+        Limits on the execution of the query, such as a timeout.
     sink_to_single_file
         Perform the sink into a single file.
 
@@ -798,6 +866,7 @@ def spawn_blocking(
         shuffle_compression=shuffle_compression,
         distributed=distributed,
         n_retries=n_retries,
+        limits=limits,
         sink_to_single_file=sink_to_single_file,
         optimizations=optimizations,
     )
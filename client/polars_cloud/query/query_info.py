@@ -9,7 +9,7 @@ if TYPE_CHECKING:
     from uuid import UUID
 
     import polars_cloud.polars_cloud as pcr
-    from polars_cloud._typing import FileType
+    from polars_cloud._typing import FileType, QueryLimit
 
 
 @dataclass
@@ -51,6 +51,11 @@ class QueryInfo:
         """
         return self.inner.errors
 
+    @property
+    def limits_hit(self) -> list[QueryLimit]:
+        """Get the limits of the query settings that the query hit."""
+        return self.inner.limits_hit
+
     @property
     def sink_dst(self) -> list[str] | None:
         """Get the sink destination uri's."""
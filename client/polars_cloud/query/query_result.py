@@ -33,7 +33,7 @@ if TYPE_CHECKING:
     from polars import DataFrame, LazyFrame
 
     import polars_cloud.polars_cloud as pcr
-    from polars_cloud._typing import FileType
+    from polars_cloud._typing import FileType, QueryLimit
     from polars_cloud.query.query import DirectQuery
     from polars_cloud.query.query_info import QueryInfo
     from polars_cloud.query.query_profile import QueryProfile
@@ -126,6 +126,15 @@ class QueryResult:
         """
         return self.finished_task_info.finished_stages
 
+    @property
+    def limits_hit(self) -> list[QueryLimit]:
+        """Limits of the query settings that the query hit while running.
+
+        A query that hit its `timeout` was canceled, a query that hit its
+        `memory_limit` spilled to disk.
+        """
+        return self.finished_task_info.limits_hit
+
     @property
     def location(self) -> list[str] | None:
         """Location where the result is written."""
@@ -56,3 +56,20 @@ class QueryStatus(Enum):
         else:
             msg = f"Unknown query status {schema}"
             raise RuntimeError(msg)
+
+    def _to_api_schema(self) -> QueryStatusCodeSchema:
+        if self == QueryStatus.QUEUED:
+            return QueryStatusCodeSchema.Queued
+        elif self == QueryStatus.SCHEDULED:
+            return QueryStatusCodeSchema.Scheduled
+        elif self == QueryStatus.INPROGRESS:
+            return QueryStatusCodeSchema.InProgress
+        elif self == QueryStatus.SUCCESS:
+            return QueryStatusCodeSchema.Success
+        elif self == QueryStatus.FAILED:
+            return QueryStatusCodeSchema.Failed
+        elif self == QueryStatus.CANCELED:
+            return QueryStatusCodeSchema.Canceled
+        else:
+            msg = f"Unknown query status {self}"
+            raise ValueError(msg)
@@ -1,12 +1,16 @@
 from __future__ import annotations
 
+import io
 import logging
 import time
 import webbrowser
-from functools import cached_property
-from typing import TYPE_CHECKING
+from collections.abc import Sequence
+from datetime import timedelta
+from typing import TYPE_CHECKING, Any
 from uuid import UUID
 
+import polars as pl
+
 import polars_cloud.polars_cloud as pcr
 from polars_cloud import constants
 from polars_cloud.exceptions import (
@@ -23,6 +27,9 @@ from polars_cloud.workspace.workspace_status import WorkspaceStatus
 
 if TYPE_CHECKING:
     import sys
+    from datetime import datetime
+
+    from polars_cloud.query.query_status import QueryStatus
 
     if sys.version_info >= (3, 11):
         from typing import Self
@@ -34,6 +41,9 @@ logger = logging.getLogger(__name__)
 POLLING_INTERVAL_SECONDS_DEFAULT = 2
 POLLING_TIMEOUT_SECONDS_DEFAULT = 300
 
+# Sentinel for defaults that have not been fetched yet, `None` means no defaults
+_UNLOADED = object()
+
 
 class Workspace:
     """Polars Workspace.
@@ -75,6 +85,7 @@ class Workspace:
         self._name = name
         self._id = id
         self._status: None | WorkspaceStatus = None
+        self._defaults: object = _UNLOADED
 
         if organization is None:
             self._organization = None
@@ -112,6 +123,7 @@ class Workspace:
     def _from_api_schema(cls, workspace_schema: pcr.WorkspaceSchema) -> Self:
         """Parse API result into a Python object."""
         self = object.__new__(cls)
+        self._defaults = _UNLOADED
         self._update_from_api_schema(workspace_schema)
         return self
 
@@ -157,16 +169,86 @@ class Workspace:
         assert self._organization is not None
         return self._organization
 
-    @cached_property
+    @property
     def defaults(self) -> WorkspaceDefaultComputeSpecs | None:
-        """Default Cluster Specification."""
-        api_defaults = constants.API_CLIENT.get_workspace_default_compute_specs(self.id)
-        if not api_defaults:
-            return None
+        """Default Cluster Specification.
+
+        Assigning new specs stores them as the workspace defaults, assigning
+        `None` or deleting the attribute removes the defaults.
+
+        Examples
+        --------
+        >>> workspace = pc.Workspace("workspace-name")
+        >>> workspace.defaults = pc.WorkspaceDefaultComputeSpecs(cpus=4, memory=8)
+        >>> del workspace.defaults
+        """
+        if self._defaults is _UNLOADED:
+            api_defaults = constants.API_CLIENT.get_workspace_default_compute_specs(
+                self.id
+            )
+            if api_defaults is None:
+                self._defaults = None
+            else:
+                self._defaults = WorkspaceDefaultComputeSpecs._from_api_schema(
+                    api_defaults
+                )
+
+        return self._defaults  # type: ignore[return-value]
+
+    @defaults.setter
+    def defaults(self, defaults: WorkspaceDefaultComputeSpecs | None) -> None:
+        if defaults is None:
+            del self.defaults
+            return
+
+        constants.API_CLIENT.set_workspace_cluster_defaults(
+            self.id,
+            cluster_size=defaults.cluster_size,
+            instance_type=defaults.instance_type,
+            big_instance_type=defaults.big_instance_type,
+            cpus=defaults.cpus,
+            ram_gb=defaults.memory,
+            big_instance_multiplier=defaults.big_instance_multiplier,
+            storage=defaults.storage,
+        )
+        self._defaults = defaults
+
+    @defaults.deleter
+    def defaults(self) -> None:
+        constants.API_CLIENT.delete_workspace_cluster_defaults(self.id)
+        self._defaults = None
 
-        defaults = WorkspaceDefaultComputeSpecs._from_api_schema(api_defaults)
+    def update(
+        self,
+        *,
+        name: str | None = None,
+        description: str | None = None,
+        idle_timeout_mins: int | None = None,
+    ) -> None:
+        """Update the workspace settings.
 
-        return defaults
+        Only the settings that are passed are changed.
+
+        Parameters
+        ----------
+        name
+            New name of the workspace.
+        description
+            New description of the workspace.
+        idle_timeout_mins
+            The time a cluster can be idle before it will be automatically killed.
+
+        Examples
+        --------
+        >>> pc.Workspace("workspace-name").update(idle_timeout_mins=30)
+        """
+        constants.API_CLIENT.patch_workspace_details(
+            self.id,
+            name=name,
+            description=description,
+            idle_timeout_mins=idle_timeout_mins,
+        )
+        self._load_by_id()
 
     @classmethod
     def _parse(
@@ -244,7 +326,18 @@ class Workspace:
         self._update_from_api_schema(workspace_details)
 
     def _load_by_default(self) -> None:
-        """Load the workspace by the default of the user."""
+        """Load the workspace by the default of the profile or the user."""
+        default = constants.API_CLIENT.profile.workspace
+        if default is not None:
+            try:
+                self._id = UUID(default)
+            except ValueError:
+                self._name = default
+                self._load_by_name()
+            else:
+                self._load_by_id()
+            return
+
         user: pcr.UserSchema = constants.API_CLIENT.get_user()
         if user.default_workspace_id is None:
             msg = (
@@ -293,41 +386,16 @@ class Workspace:
         >>> pc.Workspace("workspace-name").wait_until_active(timeout=5)
         True
         """
-        max_polls = int(timeout / interval) + 1
-        prev_status = WorkspaceStatus.Uninitialized
         logger.debug("polling workspace details endpoint")
-        for _ in range(max_polls):
+        try:
+            workspace_schema = constants.API_CLIENT.wait_for_workspace_state(
+                self.id,
+                pcr.WorkspaceStateSchema.Active,
+                interval_secs=interval,
+                timeout_secs=timeout,
+            )
+        except TimeoutError:
             self.load()
-            logger.debug("current workspace status: %s", self.status)
-
-            if self.status != prev_status:
-                # Log a message when status changes from UNINITIALIZED to PENDING
-                if self.status == WorkspaceStatus.Pending:
-                    logger.info("workspace stack is being deployed")
-                prev_status = self.status
-
-            if self.status in [
-                WorkspaceStatus.Uninitialized,
-                WorkspaceStatus.Pending,
-            ]:
-                time.sleep(interval)
-                continue
-            elif self.status == WorkspaceStatus.Active:
-                logger.info("workspace successfully verified")
-                return True
-            elif self.status == WorkspaceStatus.Failed:
-                msg = (
-                    "Deploying the workspace failed."
-                    " Check the status of the deployment in your AWS CloudFormation dashboard"
-                    f" or by following this link: {self._cloud_resources_url}"
-                )
-                logger.debug(msg)
-                raise WorkspaceDeploymentError(msg)
-            elif self.status == WorkspaceStatus.Deleted:
-                logger.info("workspace verification failed: status is %s", self.status)
-                return False
-
-        else:
             msg = (
                 "Workspace verification has timed out."
                 " Check the status of the deployment in your AWS CloudFormation dashboard"
@@ -336,11 +404,106 @@ class Workspace:
                 msg += f" or by following this link: {self._cloud_resources_url}"
 
             logger.debug(msg)
-            raise VerificationTimeoutError(msg)
+            raise VerificationTimeoutError(msg) from None
+
+        if workspace_schema is None:
+            logger.info("workspace verification failed: workspace no longer exists")
+            return False
+
+        self._update_from_api_schema(workspace_schema)
+        logger.debug("current workspace status: %s", self.status)
+
+        if self.status == WorkspaceStatus.Active:
+            logger.info("workspace successfully verified")
+            return True
+        elif self.status == WorkspaceStatus.Failed:
+            msg = (
+                "Deploying the workspace failed."
+                " Check the status of the deployment in your AWS CloudFormation dashboard"
+                f" or by following this link: {self._cloud_resources_url}"
+            )
+            logger.debug(msg)
+            raise WorkspaceDeploymentError(msg)
+        else:
+            logger.info("workspace verification failed: status is %s", self.status)
+            return False
+
+    def wait_until_deleted(
+        self,
+        *,
+        interval: int = POLLING_INTERVAL_SECONDS_DEFAULT,
+        timeout: int = POLLING_TIMEOUT_SECONDS_DEFAULT,
+    ) -> bool:
+        """Wait until the workspace is deleted.
 
-    def delete(self) -> None:
+        Parameters
+        ----------
+        interval
+            The number of seconds between each verification call.
+        timeout
+            The number of seconds before verification fails.
+
+        Examples
+        --------
+        >>> pc.Workspace("workspace-name").wait_until_deleted(timeout=5)
+        True
+        """
+        logger.debug("polling workspace details endpoint")
+        try:
+            workspace_schema = constants.API_CLIENT.wait_for_workspace_state(
+                self.id,
+                pcr.WorkspaceStateSchema.Deleted,
+                interval_secs=interval,
+                timeout_secs=timeout,
+            )
+        except TimeoutError:
+            msg = (
+                "Workspace deletion has timed out."
+                " Check the status of the stack deletion in your AWS CloudFormation dashboard"
+            )
+            if self._cloud_resources_url and len(self._cloud_resources_url) > 0:
+                msg += f" or by following this link: {self._cloud_resources_url}"
+
+            logger.debug(msg)
+            raise VerificationTimeoutError(msg) from None
+
+        if workspace_schema is None:
+            self._status = WorkspaceStatus.Deleted
+            logger.info("workspace successfully deleted")
+            return True
+
+        self._update_from_api_schema(workspace_schema)
+        if self.status == WorkspaceStatus.Deleted:
+            logger.info("workspace successfully deleted")
+            return True
+
+        msg = (
+            "Deleting the workspace failed."
+            " Check the status of the stack deletion in your AWS CloudFormation dashboard"
+            f" or by following this link: {self._cloud_resources_url}"
+        )
+        logger.debug(msg)
+        raise WorkspaceDeploymentError(msg)
+
+    def delete(
+        self,
+        *,
+        wait: bool = False,
+        interval: int = POLLING_INTERVAL_SECONDS_DEFAULT,
+        timeout: int = POLLING_TIMEOUT_SECONDS_DEFAULT,
+    ) -> None:
         """Delete a workspace.
 
+        Parameters
+        ----------
+        wait
+            Wait until the workspace is deleted, which happens once its
+            CloudFormation stack has been removed.
+        interval
+            The number of seconds between each verification call.
+        timeout
+            The number of seconds before verification fails.
+
         Examples
         --------
         >>> pc.Workspace("workspace-name").delete()
@@ -355,7 +518,12 @@ class Workspace:
         if workspace_info is not None:
             logger.debug("opening CloudFormation console")
             _open_cloudformation_console(workspace_info.stack_name, workspace_info.url)
+            if wait:
+                logger.info("verifying workspace deletion")
+                self.wait_until_deleted(interval=interval, timeout=timeout)
+                print("Successfully deleted workspace")
         else:
+            self._status = WorkspaceStatus.Deleted
             print("Successfully deleted workspace")
 
     @classmethod
@@ -400,10 +568,13 @@ class Workspace:
             workspace_name, organization_id
         )
 
+        workspace = cls._from_api_schema(workspace_schema.workspace)
+        setup_urls = constants.API_CLIENT.get_workspace_setup_url(workspace.id)
+
         logger.debug("opening web browser")
         _open_browser(workspace_schema.full_url)
+        _print_template_urls(setup_urls)
 
-        workspace = cls._from_api_schema(workspace_schema.workspace)
         if verify:
             logger.info("verifying workspace creation")
             workspace.wait_until_active()
@@ -439,6 +610,7 @@ class Workspace:
 
         logger.debug("opening web browser")
         _open_browser(setup_urls.full_setup_url)
+        _print_template_urls(setup_urls)
 
         if verify:
             logger.info("verifying workspace deployment")
@@ -446,6 +618,193 @@ class Workspace:
 
         logger.info("workspace deployment successful")
 
+    def query_history(
+        self,
+        *,
+        status: QueryStatus | Sequence[QueryStatus] | None = None,
+        cluster_id: UUID | None = None,
+        user_id: UUID | None = None,
+        label: str | None = None,
+        requested_after: datetime | None = None,
+        requested_before: datetime | None = None,
+        ended_after: datetime | None = None,
+        ended_before: datetime | None = None,
+        min_duration: timedelta | int | None = None,
+    ) -> list[pcr.QueryWithStateTimingSchema]:
+        """List the queries that were run in this workspace.
+
+        All filters are optional and are combined, only queries matching every
+        given filter are returned. Datetimes must be timezone-aware.
+
+        Parameters
+        ----------
+        status
+            Only return queries whose latest status is one of these statuses.
+        cluster_id
+            Only return queries that ran on this compute cluster.
+        user_id
+            Only return queries that were started by this user.
+        label
+            Only return queries that have a label with this name.
+        requested_after
+            Only return queries requested at or after this time.
+        requested_before
+            Only return queries requested before this time.
+        ended_after
+            Only return queries that ended at or after this time.
+        ended_before
+            Only return queries that ended before this time.
+        min_duration
+            Only return queries that ran for at least this long, either as a
+            `timedelta` or in seconds. Queries that are still running are
+            measured up to now.
+
+        Examples
+        --------
+        >>> pc.Workspace("workspace-name").query_history(
+        ...     status=pc.QueryStatus.SUCCESS, min_duration=timedelta(minutes=10)
+        ... )
+        [QueryWithStateTimingSchema(...), ...]
+        """
+        return constants.API_CLIENT.get_queries(
+            self.id,
+            **_query_history_filters(
+                status=status,
+                cluster_id=cluster_id,
+                user_id=user_id,
+                label=label,
+                requested_after=requested_after,
+                requested_before=requested_before,
+                ended_after=ended_after,
+                ended_before=ended_before,
+                min_duration=min_duration,
+            ),
+        )
+
+    def query_history_df(
+        self,
+        *,
+        status: QueryStatus | Sequence[QueryStatus] | None = None,
+        cluster_id: UUID | None = None,
+        user_id: UUID | None = None,
+        label: str | None = None,
+        requested_after: datetime | None = None,
+        requested_before: datetime | None = None,
+        ended_after: datetime | None = None,
+        ended_before: datetime | None = None,
+        min_duration: timedelta | int | None = None,
+        include_results: bool = False,
+    ) -> pl.DataFrame:
+        """Load the queries that were run in this workspace into a DataFrame.
+
+        Every row holds the identifiers, status and timestamps of a query, the
+        `queued_ms`, `running_ms` and `total_ms` durations derived from those
+        timestamps and, with `include_results`, the stage and result statistics.
+
+        Parameters
+        ----------
+        status
+            Only return queries whose latest status is one of these statuses.
+        cluster_id
+            Only return queries that ran on this compute cluster.
+        user_id
+            Only return queries that were started by this user.
+        label
+            Only return queries that have a label with this name.
+        requested_after
+            Only return queries requested at or after this time.
+        requested_before
+            Only return queries requested before this time.
+        ended_after
+            Only return queries that ended at or after this time.
+        ended_before
+            Only return queries that ended before this time.
+        min_duration
+            Only return queries that ran for at least this long, either as a
+            `timedelta` or in seconds.
+        include_results
+            Fetch the result statistics of every query, this requires one
+            request per query.
+
+        Examples
+        --------
+        >>> df = pc.Workspace("workspace-name").query_history_df(
+        ...     requested_after=datetime(2025, 1, 1, tzinfo=timezone.utc)
+        ... )
+        >>> df.group_by("cluster_id", "user_id").agg(
+        ...     pl.col("total_ms").quantile(0.95)
+        ... ).write_parquet("latency.parquet")
+        """
+        buf = constants.API_CLIENT.get_queries_ipc(
+            self.id,
+            **_query_history_filters(
+                status=status,
+                cluster_id=cluster_id,
+                user_id=user_id,
+                label=label,
+                requested_after=requested_after,
+                requested_before=requested_before,
+                ended_after=ended_after,
+                ended_before=ended_before,
+                min_duration=min_duration,
+            ),
+            include_results=include_results,
+        )
+        return pl.read_ipc_stream(io.BytesIO(buf))
+
+    def query_counts(
+        self,
+        start: datetime,
+        end: datetime,
+        *,
+        interval: timedelta = timedelta(days=1),
+        cluster_id: UUID | None = None,
+    ) -> pl.DataFrame:
+        """Count the queries in this workspace per time bucket.
+
+        Every row holds the start of a bucket of length `interval` together with
+        the total number of queries and how many of those succeeded, failed or
+        are still in progress.
+
+        Parameters
+        ----------
+        start
+            Start of the time window, must be timezone-aware.
+        end
+            End of the time window, must be timezone-aware.
+        interval
+            Length of each bucket.
+        cluster_id
+            Only count queries that ran on this compute cluster.
+
+        Examples
+        --------
+        >>> now = datetime.now(timezone.utc)
+        >>> pc.Workspace("workspace-name").query_counts(
+        ...     now - timedelta(weeks=1), now, interval=timedelta(hours=1)
+        ... )
+        shape: (168, 5)
+        """
+        counts = constants.API_CLIENT.get_query_count(
+            self.id, start, end, interval, cluster_id=cluster_id
+        )
+        return pl.DataFrame(
+            {
+                "timestamp": [c.timestamp for c in counts],
+                "count": [c.count for c in counts],
+                "count_successful": [c.count_successful for c in counts],
+                "count_failed": [c.count_failed for c in counts],
+                "count_in_progress": [c.count_in_progress for c in counts],
+            },
+            schema={
+                "timestamp": pl.Datetime("us", "UTC"),
+                "count": pl.Int64,
+                "count_successful": pl.Int64,
+                "count_failed": pl.Int64,
+                "count_in_progress": pl.Int64,
+            },
+        )
+
     @classmethod
     def list(cls, name: str | None = None) -> list[Workspace]:
         """List all workspaces the user has access to.
@@ -483,6 +842,49 @@ def _open_browser(url: str) -> None:
     )
 
 
+def _query_history_filters(
+    *,
+    status: QueryStatus | Sequence[QueryStatus] | None,
+    cluster_id: UUID | None,
+    user_id: UUID | None,
+    label: str | None,
+    requested_after: datetime | None,
+    requested_before: datetime | None,
+    ended_after: datetime | None,
+    ended_before: datetime | None,
+    min_duration: timedelta | int | None,
+) -> dict[str, Any]:
+    """Convert the query history filters into keyword arguments of the API client."""
+    status_schemas = None
+    if isinstance(status, Sequence):
+        status_schemas = [s._to_api_schema() for s in status]
+    elif status is not None:
+        status_schemas = [status._to_api_schema()]
+    if isinstance(min_duration, timedelta):
+        min_duration = int(min_duration.total_seconds())
+
+    return {
+        "cluster_id": cluster_id,
+        "user_id": user_id,
+        "status": status_schemas,
+        "label": label,
+        "requested_after": requested_after,
+        "requested_before": requested_before,
+        "ended_after": ended_after,
+        "ended_before": ended_before,
+        "min_duration_secs": min_duration,
+    }
+
+
+def _print_template_urls(setup_urls: pcr.WorkspaceSetupUrlSchema) -> None:
+    """Print the CloudFormation templates for users deploying the stack themselves."""
+    print(
+        "To deploy the stack with your own tooling, use one of these CloudFormation templates:\n"
+        f"  full:      {setup_urls.full_template_url}\n"
+        f"  barebones: {setup_urls.barebones_template_url}"
+    )
+
+
 def _open_cloudformation_console(stack_name: str, url: str) -> None:
     print(
         f"To delete your workspace, remove the {stack_name} CloudFormation stack in AWS, \n"
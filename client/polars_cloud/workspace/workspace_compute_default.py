@@ -25,12 +25,17 @@ class WorkspaceDefaultComputeSpecs:
         The number of nodes in the cluster
     instance_type
         The AWS instance type of each node (e.g. t2.large)
+    big_instance_type
+        The AWS instance type of the big node, only valid with `instance_type`
     memory
         The amount of RAM memory in GB for each node
     cpus
         The amount of vCPU cores for each node
     storage
         The amount of disk storage on each node
+    big_instance_multiplier
+        How many times the resources of a regular node the big node gets,
+        only valid with `cpus` and `memory`
 
     Examples
     --------
@@ -46,10 +51,14 @@ class WorkspaceDefaultComputeSpecs:
         cpus: int | None = None,
         memory: int | None = None,
         storage: int | None = None,
+        big_instance_type: str | None = None,
+        big_instance_multiplier: int | None = None,
     ):
         self.instance_type = instance_type
+        self.big_instance_type = big_instance_type
         self.memory = memory
         self.cpus = cpus
+        self.big_instance_multiplier = big_instance_multiplier
         self.storage = storage
         self.cluster_size = cluster_size
 
@@ -57,8 +66,10 @@ class WorkspaceDefaultComputeSpecs:
     def _from_api_schema(cls, value: pcr.DefaultComputeSpecs) -> Self:
         return cls(
             instance_type=value.instance_type,
+            big_instance_type=value.big_instance_type,
             memory=value.ram_gb,
             cpus=value.cpus,
+            big_instance_multiplier=value.big_instance_multiplier,
             storage=value.storage,
             cluster_size=value.cluster_size,
         )
@@ -67,8 +78,10 @@ class WorkspaceDefaultComputeSpecs:
         return (
             f"{self.__class__.__name__}("
             f"instance_type={self.instance_type!r}, "
+            f"big_instance_type={self.big_instance_type!r}, "
             f"cpus={self.cpus!r} vCPU cores, "
             f"memory={self.memory!r} GB, "
+            f"big_instance_multiplier={self.big_instance_multiplier!r}, "
             f"storage={self.storage!r} GB, "
             f"cluster_size={self.cluster_size!r} )"
         )
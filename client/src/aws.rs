@@ -1,13 +1,19 @@
 #![allow(clippy::result_large_err)]
 
+use std::time::{Duration, Instant};
+
 use polars_axum_models::{
-    DeleteWorkspaceSchema, WorkSpaceArgs, WorkspaceSetupUrlSchema, WorkspaceWithUrlSchema,
+    DeleteWorkspaceSchema, WorkSpaceArgs, WorkspaceSchema, WorkspaceSetupUrlSchema,
+    WorkspaceStateSchema, WorkspaceWithUrlSchema,
 };
 use polars_backend_client::client::ApiClient;
+use pyo3::exceptions::PyTimeoutError;
 use pyo3::{Python, pymethods};
+use reqwest::StatusCode;
 use uuid::Uuid;
 
 use crate::client::WrappedAPIClient;
+use crate::constants::RUNTIME;
 use crate::error::ApiError;
 
 #[pymethods]
@@ -49,4 +55,54 @@ impl WrappedAPIClient {
             client.delete_workspace(workspace_id)
         })
     }
+
+    /// Poll the workspace until it reaches `state` or can no longer progress.
+    ///
+    /// Polling stops early when the workspace ends up `Failed` or `Deleted`, the
+    /// caller is expected to inspect the returned status. Returns `None` when
+    /// the workspace no longer exists and raises a `TimeoutError` when
+    /// `timeout_secs` elapses first.
+    #[pyo3(signature=(workspace_id, state, *, interval_secs=2, timeout_secs=300))]
+    pub fn wait_for_workspace_state(
+        &mut self,
+        py: Python<'_>,
+        workspace_id: Uuid,
+        state: WorkspaceStateSchema,
+        interval_secs: u64,
+        timeout_secs: u64,
+    ) -> Result<Option<WorkspaceSchema>, ApiError> {
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+        let mut prev_status = None;
+        loop {
+            let workspace = match self.get_workspace(py, workspace_id) {
+                Ok(workspace) => workspace,
+                Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => return Ok(None),
+                Err(e) => return Err(e),
+            };
+
+            if prev_status.as_ref() != Some(&workspace.status) {
+                tracing::debug!("workspace {workspace_id} is {:?}", workspace.status);
+                prev_status = Some(workspace.status.clone());
+            }
+
+            if workspace.status == state
+                || matches!(
+                    workspace.status,
+                    WorkspaceStateSchema::Failed | WorkspaceStateSchema::Deleted
+                )
+            {
+                return Ok(Some(workspace));
+            }
+
+            if Instant::now() >= deadline {
+                return Err(PyTimeoutError::new_err(format!(
+                    "workspace did not reach state {state:?} within {timeout_secs} seconds, current state is {:?}",
+                    workspace.status
+                ))
+                .into());
+            }
+
+            RUNTIME.block_on(py, tokio::time::sleep(Duration::from_secs(interval_secs)))?;
+        }
+    }
 }
@@ -1,55 +1,70 @@
 #![allow(clippy::result_large_err)]
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use polars_axum_models::Paginated;
 use polars_backend_client::client::ApiClient;
 use polars_backend_client::error::ApiError as ClientApiError;
-use protos_common::tonic::{Request, Status};
+use protos_common::Compression;
+use protos_common::tonic::{Code, Request, Status};
 use pyo3::exceptions::PyValueError;
-use pyo3::{Python, pyclass, pymethods};
+use pyo3::{Py, PyAny, Python, pyclass, pymethods};
 
 use crate::VERSIONS;
 use crate::client::grpc::{ControlPlaneGRPCClient, get_control_plane_client};
 use crate::client::login::login_new;
-use crate::client::{AuthError, AuthMethod, AuthToken};
-use crate::constants::{API_ADDR, RUNTIME};
+use crate::client::token_manager::{AuthMiddleware, DEFAULT_TOKEN_REFRESH_SKEW, TokenManager};
+use crate::client::token_store::FileTokenStore;
+use crate::client::{AuthError, AuthToken, LogoutResult, Profile, TokenClaims};
+use crate::constants::RUNTIME;
 use crate::error::ApiError;
 
 #[pyclass(name = "ApiClient")]
 pub struct WrappedAPIClient {
-    rest: ApiClient,
+    pub(crate) rest: ApiClient,
     grpc: ControlPlaneGRPCClient,
-    auth_token: Option<AuthToken>,
+    pub(crate) grpc_compression: Compression,
+    pub(crate) tokens: TokenManager,
 }
 
-impl Default for WrappedAPIClient {
-    fn default() -> Self {
+impl WrappedAPIClient {
+    fn with_profile(profile: Profile, refresh_skew: Duration) -> Self {
         let versions = VERSIONS.get().unwrap().clone().unwrap();
-        let rest =
-            ApiClient::new_with_versions("PLACEHOLDER".to_string(), API_ADDR.clone(), versions.1);
-        let grpc = get_control_plane_client();
+        let api_addr = profile.api_addr();
+        let grpc = get_control_plane_client(&api_addr);
+        let grpc_compression = profile.grpc_compression();
+
+        // The auth server is called without the auth middleware, otherwise a
+        // refresh would wait on itself.
+        let auth_pool = ApiClient::builder()
+            .with_retries()
+            .with_versions(versions.1.clone())
+            .build(String::new(), profile.auth_domain())
+            .client;
+        let store = Arc::new(FileTokenStore::new(&profile));
+        let tokens = TokenManager::new(profile, store, auth_pool, refresh_skew);
+        let rest = ApiClient::builder()
+            .with_retries()
+            .with_versions(versions.1)
+            .with_middleware(AuthMiddleware(tokens.clone()))
+            .build("PLACEHOLDER".to_string(), api_addr);
         WrappedAPIClient {
             rest,
             grpc,
-            auth_token: None,
+            grpc_compression,
+            tokens,
         }
     }
-}
 
-impl WrappedAPIClient {
     async fn set_or_refresh_auth(&mut self) -> Result<(), AuthError> {
-        let connection_pool = self.rest.client.clone();
-        if let Some(token) = self.auth_token.as_mut() {
-            token.refresh(connection_pool).await?;
-        } else {
-            self.auth_token = Some(AuthToken::new(connection_pool).await?)
-        }
-        let auth_header = self.auth_token.as_ref().unwrap().to_auth_header();
+        let auth_header = self.tokens.auth_header().await?;
         self.rest.set_auth_header(auth_header);
         Ok(())
     }
 
-    fn get_auth_method(&self) -> Option<AuthMethod> {
-        self.auth_token.as_ref().map(|t| t.method())
+    async fn api_error(&self, e: ClientApiError) -> ApiError {
+        ApiError::from_with_auth_method(e, self.tokens.method().await)
     }
 
     pub(crate) fn call<'a, T: Send, F, F2>(
@@ -62,28 +77,56 @@ impl WrappedAPIClient {
         F2: Future<Output = Result<T, ClientApiError>> + Send + 'a,
     {
         RUNTIME.block_on(py, self.set_or_refresh_auth())??;
-        RUNTIME
-            .block_on(py, f(&self.rest))?
-            .map_err(|e| ApiError::from_with_auth_method(e, self.get_auth_method()))
+        let this: &'a Self = self;
+        let response = f(&this.rest);
+        RUNTIME.block_on(py, async move {
+            match response.await {
+                Ok(response) => Ok(response),
+                Err(e) => Err(this.api_error(e).await),
+            }
+        })?
     }
 
+    /// Send the message built by `message` with `f`, retrying once with a
+    /// renewed token if the server rejects the current one, like the REST requests.
+    ///
+    /// `message` is called again only for the retry, so large messages are not
+    /// copied up front just in case.
     pub(crate) fn call_grpc<'a, T: Send, U, F, F2>(
         &'a mut self,
         py: Python<'a>,
         f: F,
-        mut request: Request<U>,
+        mut message: impl FnMut() -> U,
     ) -> Result<T, ApiError>
     where
-        F: FnOnce(ControlPlaneGRPCClient, Request<U>) -> F2,
+        F: Fn(ControlPlaneGRPCClient, Request<U>) -> F2,
         F2: Future<Output = Result<T, Status>> + Send + 'a,
     {
+        fn authorized<U>(message: U, header: &str) -> Request<U> {
+            let mut request = Request::new(message);
+            request
+                .metadata_mut()
+                .insert("authorization", header.parse().unwrap());
+            request
+        }
+
         RUNTIME.block_on(py, self.set_or_refresh_auth())??;
-        request
-            .metadata_mut()
-            .insert("authorization", self.rest.auth_header.parse().unwrap());
-        RUNTIME
-            .block_on(py, f(self.grpc.clone(), request))?
-            .map_err(ApiError::from)
+        let header = self.rest.auth_header.clone();
+        let request = authorized(message(), &header);
+        match RUNTIME.block_on(py, f(self.grpc.clone(), request))? {
+            Err(status) if status.code() == Code::Unauthenticated => {
+                let Some(renewed) = RUNTIME.block_on(py, self.tokens.renew_rejected(&header))??
+                else {
+                    return Err(status.into());
+                };
+                self.rest.set_auth_header(renewed.clone());
+                let request = authorized(message(), &renewed);
+                RUNTIME
+                    .block_on(py, f(self.grpc.clone(), request))?
+                    .map_err(ApiError::from)
+            },
+            result => result.map_err(ApiError::from),
+        }
     }
 
     pub(crate) fn call_paginated<'a, T: Send, F, F2>(
@@ -96,12 +139,17 @@ impl WrappedAPIClient {
         F2: Future<Output = Result<Paginated<T>, ClientApiError>> + Send + 'a,
     {
         RUNTIME.block_on(py, self.set_or_refresh_auth())??;
+        let this: &'a Self = self;
         let mut results = Vec::with_capacity(25);
 
         for page in 1..10 {
-            let mut paginated_response = RUNTIME
-                .block_on(py, f(&self.rest, page))?
-                .map_err(|e| ApiError::from_with_auth_method(e, self.get_auth_method()))?;
+            let response = f(&this.rest, page);
+            let mut paginated_response = RUNTIME.block_on(py, async {
+                match response.await {
+                    Ok(response) => Ok(response),
+                    Err(e) => Err(this.api_error(e).await),
+                }
+            })??;
 
             results.append(&mut paginated_response.result);
 
@@ -115,19 +163,54 @@ impl WrappedAPIClient {
 
 #[pymethods]
 impl WrappedAPIClient {
+    /// Tokens are refreshed once they expire within `token_refresh_skew`.
     #[new]
-    fn new() -> Self {
-        Default::default()
+    #[pyo3(signature = (profile=None, *, token_refresh_skew=None))]
+    fn new(
+        profile: Option<String>,
+        token_refresh_skew: Option<Duration>,
+    ) -> Result<Self, ApiError> {
+        Ok(Self::with_profile(
+            Profile::load(profile)?,
+            token_refresh_skew.unwrap_or(DEFAULT_TOKEN_REFRESH_SKEW),
+        ))
+    }
+
+    /// The configuration profile this client was created with.
+    #[getter]
+    fn profile(&self) -> Profile {
+        self.tokens.profile().clone()
     }
 
-    fn login(&mut self, py: Python<'_>) -> Result<(), ApiError> {
-        let token = RUNTIME.block_on(py, login_new(self.rest.client.clone()))??;
-        self.auth_token = Some(token);
+    /// Log in interactively, `progress` is called with a `DeviceLoginProgress`
+    /// while waiting for a device login.
+    #[pyo3(signature = (*, progress=None))]
+    fn login(&mut self, py: Python<'_>, progress: Option<Py<PyAny>>) -> Result<(), ApiError> {
+        let token = RUNTIME.block_on(
+            py,
+            login_new(
+                self.tokens.profile(),
+                self.tokens.store(),
+                self.tokens.connection_pool(),
+                progress.as_ref(),
+            ),
+        )??;
+        RUNTIME.block_on(py, self.tokens.set(token))?;
         Ok(())
     }
 
-    fn clear_authentication(&mut self) {
-        self.auth_token = None
+    /// Revoke and remove the stored tokens of the profile.
+    fn logout(&mut self, py: Python<'_>) -> Result<LogoutResult, ApiError> {
+        Ok(RUNTIME.block_on(py, self.tokens.logout())??)
+    }
+
+    fn clear_authentication(&mut self, py: Python<'_>) -> Result<(), ApiError> {
+        Ok(RUNTIME.block_on(py, self.tokens.clear())?)
+    }
+
+    /// Claims of the access token, after validating it against the auth server keys.
+    fn get_token_claims(&mut self, py: Python<'_>) -> Result<TokenClaims, ApiError> {
+        Ok(RUNTIME.block_on(py, self.tokens.claims())??)
     }
 
     fn get_auth_header(&mut self, py: Python<'_>) -> Result<String, ApiError> {
@@ -145,11 +228,19 @@ impl WrappedAPIClient {
     ) -> Result<(), ApiError> {
         match (client_id.clone(), client_secret) {
             (Some(client_id), Some(client_secret)) => {
-                let client_clone = self.rest.client.clone();
+                let client_clone = self.tokens.connection_pool();
+                let profile = self.tokens.profile();
                 let token = RUNTIME.block_on(py, async move {
-                    AuthToken::from_service_account(client_id, client_secret, client_clone).await
+                    AuthToken::from_service_account(
+                        profile,
+                        client_id,
+                        client_secret,
+                        true,
+                        client_clone,
+                    )
+                    .await
                 })??;
-                self.auth_token = Some(token);
+                RUNTIME.block_on(py, self.tokens.set(token))?;
             },
             (Some(_), None) | (None, Some(_)) => {
                 return Err(PyValueError::new_err(
@@ -166,7 +257,7 @@ impl WrappedAPIClient {
                 if !interactive || client_id.is_some() {
                     Err(e)
                 } else {
-                    self.login(py)
+                    self.login(py, None)
                 }
             },
         }
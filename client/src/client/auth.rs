@@ -1,27 +1,82 @@
 use std::fmt::Formatter;
+use std::path::PathBuf;
+use std::time::Duration;
 use std::{fmt, fs};
 
 use pyo3::pyclass;
+use serde::Deserialize;
 
 use crate::client::error::AuthError;
+use crate::client::jwt::ExpectedAudience;
+use crate::client::token_store::{StoredTokens, TokenStore};
 use crate::client::utils::{
-    get_access_token_for_service_account, get_auth_header_from_access_token_env, is_token_expired,
-    is_token_expired_user_friendly_error, token_as_header, use_refresh_token,
+    exchange_federated_token, get_access_token_for_service_account,
+    get_auth_header_from_access_token_env, is_token_expired, is_token_expired_user_friendly_error,
+    revoke_refresh_token, token_as_header, use_refresh_token,
+};
+use crate::client::{CredentialSource, Profile};
+use crate::constants::{
+    ACCESS_TOKEN_ENV, CLIENT_ID_ENV, CLIENT_SECRET_ENV, CLIENT_SECRET_FILE_ENV,
+    CREDENTIAL_PROCESS_ENV, FEDERATED_TOKEN_ENV, FEDERATED_TOKEN_FILE_ENV, LOGIN_AUDIENCE,
 };
-use crate::constants::{ACCESS_TOKEN_ENV, ACCESS_TOKEN_PATH, CONFIG_DIR, REFRESH_TOKEN_PATH};
 
 #[derive(Clone, Debug)]
 pub enum AuthToken {
     EnvVar(String),
     ServiceAccount {
         client_id: String,
-        client_secret: String,
+        /// `None` if the credentials come from the environment or the profile,
+        /// they are read again on every renewal to pick up a rotated secret.
+        client_secret: Option<String>,
         token: String,
     },
     AccessToken {
         token: String,
         refresh_token: String,
     },
+    Federated {
+        source: FederatedTokenSource,
+        token: String,
+    },
+}
+
+/// Where the external OIDC token of a workload is read from.
+#[derive(Clone, Debug)]
+pub enum FederatedTokenSource {
+    /// A file kept up to date by the platform, such as a projected Kubernetes
+    /// service account token.
+    File(PathBuf),
+    /// The `POLARS_CLOUD_FEDERATED_TOKEN` environment variable.
+    EnvVar,
+}
+
+impl FederatedTokenSource {
+    fn from_profile(profile: &Profile) -> Option<Self> {
+        if let Some(path) = profile.federated_token_file() {
+            Some(FederatedTokenSource::File(PathBuf::from(path)))
+        } else if std::env::var_os(FEDERATED_TOKEN_ENV).is_some() {
+            Some(FederatedTokenSource::EnvVar)
+        } else {
+            None
+        }
+    }
+
+    /// Read the current external token.
+    ///
+    /// The token is read again for every exchange, as the platform rotates it.
+    fn read(&self) -> Result<String, AuthError> {
+        let token = match self {
+            FederatedTokenSource::File(path) => fs::read_to_string(path).map_err(|e| {
+                AuthError::new(&format!(
+                    "Failed to read the federated token from {}: {e}.",
+                    path.display()
+                ))
+            })?,
+            FederatedTokenSource::EnvVar => std::env::var(FEDERATED_TOKEN_ENV)
+                .map_err(|_| AuthError::new(&format!("{FEDERATED_TOKEN_ENV} is not set.")))?,
+        };
+        Ok(token.trim().to_string())
+    }
 }
 
 #[pyclass]
@@ -30,14 +85,41 @@ pub enum AuthMethod {
     EnvVar,
     ServiceAccount,
     AccessToken,
+    Federated,
 }
 
 impl AuthToken {
+    /// Who the token must be issued for.
+    ///
+    /// Only interactive logins request the login audience. Service account
+    /// tokens are checked to be issued to their client, and tokens from the
+    /// environment or a token exchange carry whatever audience they were issued
+    /// with.
+    pub(crate) fn expected_audience(&self) -> ExpectedAudience<'_> {
+        match self {
+            AuthToken::AccessToken { .. } => ExpectedAudience::Audience(LOGIN_AUDIENCE),
+            AuthToken::ServiceAccount { client_id, .. } => {
+                ExpectedAudience::AuthorizedParty(client_id)
+            },
+            AuthToken::EnvVar(_) | AuthToken::Federated { .. } => ExpectedAudience::Any,
+        }
+    }
+
     pub(crate) fn method(&self) -> AuthMethod {
         match &self {
             AuthToken::EnvVar(_) => AuthMethod::EnvVar,
             AuthToken::ServiceAccount { .. } => AuthMethod::ServiceAccount,
             AuthToken::AccessToken { .. } => AuthMethod::AccessToken,
+            AuthToken::Federated { .. } => AuthMethod::Federated,
+        }
+    }
+
+    pub(crate) fn credential_source(&self) -> CredentialSource {
+        match &self {
+            AuthToken::EnvVar(_) => CredentialSource::Environment,
+            AuthToken::ServiceAccount { .. } => CredentialSource::ServiceAccount,
+            AuthToken::AccessToken { .. } => CredentialSource::Login,
+            AuthToken::Federated { .. } => CredentialSource::Federated,
         }
     }
 }
@@ -55,79 +137,304 @@ impl fmt::Display for AuthMethod {
             AccessToken => {
                 write!(f, "Access token")
             },
+            Federated => {
+                write!(f, "Workload identity federation")
+            },
         }
     }
 }
 
+/// Service account credentials printed by a `credential_process` command.
+#[derive(Deserialize)]
+struct ProcessCredentials {
+    #[serde(default)]
+    client_id: Option<String>,
+    client_secret: String,
+}
+
+/// The client secret in `POLARS_CLOUD_CLIENT_SECRET`, unless the profile
+/// takes the secret from a file or a credential process.
+fn client_secret_env(profile: &Profile) -> Option<String> {
+    if profile.client_secret_file().is_some() || profile.credential_process().is_some() {
+        return None;
+    }
+    std::env::var(CLIENT_SECRET_ENV).ok()
+}
+
+/// Whether service account credentials are configured, without reading the secret.
+fn has_service_account(profile: &Profile) -> bool {
+    profile.credential_process().is_some()
+        || (profile.client_id().is_some()
+            && (profile.client_secret_file().is_some() || client_secret_env(profile).is_some()))
+}
+
+/// Client id and secret of a service account.
+///
+/// The secret is taken from the secret file, then from the credential process
+/// of the profile and finally from `POLARS_CLOUD_CLIENT_SECRET`.
+async fn service_account_from_env(
+    profile: &Profile,
+) -> Result<Option<(String, String)>, AuthError> {
+    let client_id = profile.client_id().map(str::to_string);
+    if let Some(client_id) = &client_id
+        && let Some(path) = profile.client_secret_file()
+    {
+        let client_secret = fs::read_to_string(path).map_err(|e| {
+            AuthError::new(&format!(
+                "Failed to read the client secret from {path}: {e}."
+            ))
+        })?;
+        return Ok(Some((client_id.clone(), client_secret.trim().to_string())));
+    }
+
+    let Some(command) = profile.credential_process() else {
+        return Ok(client_id.zip(client_secret_env(profile)));
+    };
+    let credentials = run_credential_process(command).await?;
+    let client_id = credentials.client_id.or(client_id).ok_or_else(|| {
+        AuthError::new(&format!(
+            "The credential process did not print a client_id and profile '{}' has none.",
+            profile.name()
+        ))
+    })?;
+    Ok(Some((client_id, credentials.client_secret)))
+}
+
+/// Run a `credential_process` command and parse the JSON it prints.
+///
+/// The output holds a secret, so it is never included in error messages.
+async fn run_credential_process(command: &str) -> Result<ProcessCredentials, AuthError> {
+    let args = shlex::split(command)
+        .filter(|args| !args.is_empty())
+        .ok_or_else(|| AuthError::new(&format!("Invalid credential process '{command}'.")))?;
+    let output = tokio::process::Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .await
+        .map_err(|e| {
+            AuthError::new(&format!(
+                "Failed to run the credential process '{}': {e}.",
+                args[0]
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(AuthError::new(&format!(
+            "The credential process '{}' failed with {}.",
+            args[0], output.status
+        )));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        AuthError::new(&format!(
+            "The credential process '{}' printed invalid credentials at line {}, column {}, expected a JSON object with a client_secret.",
+            args[0],
+            e.line(),
+            e.column()
+        ))
+    })
+}
+
+/// The credential source [`AuthToken::new`] would pick, without contacting the auth server.
+///
+/// Must be called under the lock of `store`.
+pub(crate) fn available_credential_source(
+    profile: &Profile,
+    store: &dyn TokenStore,
+) -> Option<CredentialSource> {
+    let source = profile.credential_source();
+    if matches!(source, None | Some(CredentialSource::Environment))
+        && std::env::var_os(ACCESS_TOKEN_ENV).is_some()
+    {
+        Some(CredentialSource::Environment)
+    } else if matches!(source, None | Some(CredentialSource::Federated))
+        && FederatedTokenSource::from_profile(profile).is_some()
+    {
+        Some(CredentialSource::Federated)
+    } else if matches!(source, None | Some(CredentialSource::ServiceAccount))
+        && has_service_account(profile)
+    {
+        Some(CredentialSource::ServiceAccount)
+    } else if matches!(source, None | Some(CredentialSource::Login))
+        && store.load().ok().flatten().is_some()
+    {
+        Some(CredentialSource::Login)
+    } else {
+        None
+    }
+}
+
+/// Outcome of a logout.
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct LogoutResult {
+    /// Whether a stored refresh token was revoked at the auth server.
+    revoked: bool,
+    /// Credential source that is still used to authenticate after logging out.
+    remaining_credential_source: Option<CredentialSource>,
+}
+
 impl AuthToken {
+    /// Service account token, keeping the secret for renewals if `keep_secret`.
+    ///
+    /// Credentials resolved by [`service_account_from_env`] are not kept, they
+    /// are resolved again on renewal to pick up a rotated secret.
     pub(crate) async fn from_service_account(
+        profile: &Profile,
         client_id: String,
         client_secret: String,
+        keep_secret: bool,
         connection_pool: reqwest_middleware::ClientWithMiddleware,
     ) -> Result<Self, AuthError> {
-        let token =
-            get_access_token_for_service_account(&client_id, &client_secret, connection_pool)
-                .await?;
+        let token = get_access_token_for_service_account(
+            profile,
+            &client_id,
+            &client_secret,
+            connection_pool,
+        )
+        .await?;
         Ok(AuthToken::ServiceAccount {
             client_id,
-            client_secret,
+            client_secret: keep_secret.then_some(client_secret),
             token,
         })
     }
 
+    pub(crate) async fn from_federated_token(
+        profile: &Profile,
+        source: FederatedTokenSource,
+        connection_pool: reqwest_middleware::ClientWithMiddleware,
+    ) -> Result<Self, AuthError> {
+        let token = exchange_federated_token(profile, &source.read()?, connection_pool).await?;
+        Ok(AuthToken::Federated { source, token })
+    }
+
     pub(crate) async fn new(
+        profile: &Profile,
+        store: &dyn TokenStore,
         connection_pool: reqwest_middleware::ClientWithMiddleware,
+        refresh_skew: Duration,
     ) -> Result<Self, AuthError> {
+        let source = profile.credential_source();
+
         // Check if we can find a valid token from the env vars
-        if let Some(token) = get_auth_header_from_access_token_env()? {
+        if matches!(source, None | Some(CredentialSource::Environment))
+            && let Some(token) = get_auth_header_from_access_token_env()?
+        {
             return Ok(AuthToken::EnvVar(token));
         }
 
+        // Check if the workload has an external token to exchange
+        if matches!(source, None | Some(CredentialSource::Federated))
+            && let Some(federated) = FederatedTokenSource::from_profile(profile)
+        {
+            return Self::from_federated_token(profile, federated, connection_pool).await;
+        }
+
         // Check if we can find env var client_id / secret
-        if let (Some(client_id), Some(client_secret)) = (
-            std::env::var("POLARS_CLOUD_CLIENT_ID").ok(),
-            std::env::var("POLARS_CLOUD_CLIENT_SECRET").ok(),
-        ) {
-            let token =
-                get_access_token_for_service_account(&client_id, &client_secret, connection_pool)
-                    .await?;
-            return Ok(AuthToken::ServiceAccount {
+        if matches!(source, None | Some(CredentialSource::ServiceAccount))
+            && let Some((client_id, client_secret)) = service_account_from_env(profile).await?
+        {
+            return Self::from_service_account(
+                profile,
                 client_id,
                 client_secret,
-                token,
-            });
+                false,
+                connection_pool,
+            )
+            .await;
         }
 
-        // Look for a valid access token on disk, refresh if necessary
-        let mut token = Self::read_tokens_from_disk()?;
-        token.refresh(connection_pool).await?;
-        Ok(token)
+        match source {
+            Some(CredentialSource::Environment) => Err(AuthError::new(&format!(
+                "Profile '{}' takes its credentials from {ACCESS_TOKEN_ENV}, which is not set.",
+                profile.name()
+            ))),
+            Some(CredentialSource::ServiceAccount) => Err(AuthError::new(&format!(
+                "Profile '{}' uses a service account, but neither {CLIENT_ID_ENV} with {CLIENT_SECRET_ENV} or {CLIENT_SECRET_FILE_ENV}, nor {CREDENTIAL_PROCESS_ENV} is set.",
+                profile.name()
+            ))),
+            Some(CredentialSource::Federated) => Err(AuthError::new(&format!(
+                "Profile '{}' uses workload identity federation, but neither {FEDERATED_TOKEN_FILE_ENV} nor {FEDERATED_TOKEN_ENV} is set.",
+                profile.name()
+            ))),
+            None | Some(CredentialSource::Login) => {
+                // Look for a valid stored access token, refresh if necessary
+                let tokens = {
+                    let _lock = store.lock().await?;
+                    store.load()?
+                }
+                .ok_or_else(|| AuthError::new("Authentication token was not found."))?;
+                let mut token = AuthToken::from(tokens);
+                token
+                    .refresh(profile, store, connection_pool, refresh_skew)
+                    .await?;
+                Ok(token)
+            },
+        }
     }
+    /// Refresh the token if it expires within `refresh_skew`.
     pub async fn refresh(
         &mut self,
+        profile: &Profile,
+        store: &dyn TokenStore,
         connection_pool: reqwest_middleware::ClientWithMiddleware,
+        refresh_skew: Duration,
     ) -> Result<(), AuthError> {
-        match self {
-            AuthToken::EnvVar(token) => {
-                if is_token_expired(token, None)? {
-                    Err(AuthError::new(
-                        "Token provided in environment variable is expired.",
-                    ))
-                } else {
-                    Ok(())
-                }
+        let expiring = match self {
+            // A token from the environment can not be refreshed, so it is only
+            // rejected once it actually expired
+            AuthToken::EnvVar(token) => is_token_expired(token, None)?,
+            AuthToken::ServiceAccount { token, .. } | AuthToken::Federated { token, .. } => {
+                is_token_expired(token, Some(refresh_skew))?
+            },
+            AuthToken::AccessToken { token, .. } => {
+                is_token_expired_user_friendly_error(token, Some(refresh_skew))?
             },
+        };
+        if !expiring {
+            return Ok(());
+        }
+        self.renew(profile, store, connection_pool, refresh_skew)
+            .await
+    }
+
+    /// Whether a new token can be obtained without user interaction.
+    pub(crate) fn can_renew(&self) -> bool {
+        !matches!(self, AuthToken::EnvVar(_))
+    }
+
+    /// Obtain a new token regardless of the expiry of the current one.
+    ///
+    /// Stored tokens are renewed under the lock of the store. If another process
+    /// stored tokens that are valid for at least `refresh_skew` in the meantime,
+    /// those are used instead of refreshing again.
+    pub(crate) async fn renew(
+        &mut self,
+        profile: &Profile,
+        store: &dyn TokenStore,
+        connection_pool: reqwest_middleware::ClientWithMiddleware,
+        refresh_skew: Duration,
+    ) -> Result<(), AuthError> {
+        match self {
+            AuthToken::EnvVar(_) => Err(AuthError::new(
+                "Token provided in environment variable is expired.",
+            )),
             AuthToken::ServiceAccount {
                 client_id,
                 client_secret,
                 token,
             } => {
-                if !is_token_expired(token, None)? {
-                    return Ok(());
-                }
+                let (id, secret) = match client_secret {
+                    Some(secret) => (client_id.clone(), secret.clone()),
+                    None => service_account_from_env(profile).await?.ok_or_else(|| {
+                        AuthError::new("The service account credentials are no longer set.")
+                    })?,
+                };
                 let refreshed_token =
-                    get_access_token_for_service_account(client_id, client_secret, connection_pool)
+                    get_access_token_for_service_account(profile, &id, &secret, connection_pool)
                         .await?;
+                *client_id = id;
                 *token = refreshed_token;
                 Ok(())
             },
@@ -135,31 +442,102 @@ impl AuthToken {
                 token,
                 refresh_token,
             } => {
-                if !is_token_expired_user_friendly_error(token, None)? {
+                let _lock = store.lock().await?;
+                if let Some(stored) = store.load()?
+                    && stored.access_token != *token
+                    && matches!(
+                        is_token_expired(&stored.access_token, Some(refresh_skew)),
+                        Ok(false)
+                    )
+                {
+                    tracing::debug!("using tokens refreshed by another process");
+                    *token = stored.access_token;
+                    *refresh_token = stored.refresh_token;
                     return Ok(());
                 }
-                let tokens = use_refresh_token(refresh_token, connection_pool).await?;
-                *token = tokens.access_token;
-                *refresh_token = tokens.refresh_token;
+
+                let tokens = use_refresh_token(profile, refresh_token, connection_pool).await?;
+                // The previous refresh token is rotated out, so the new tokens are
+                // kept even if they cannot be stored.
+                *token = tokens.access_token.clone();
+                *refresh_token = tokens.refresh_token.clone();
+                if let Err(e) = store.save(&StoredTokens {
+                    access_token: tokens.access_token,
+                    refresh_token: tokens.refresh_token,
+                }) {
+                    tracing::debug!("failed to store refreshed tokens: {e}");
+                }
+                Ok(())
+            },
+            AuthToken::Federated { source, token } => {
+                *token =
+                    exchange_federated_token(profile, &source.read()?, connection_pool).await?;
                 Ok(())
             },
         }
     }
-    pub fn to_auth_header(&self) -> String {
-        let token = match &self {
+    pub(crate) fn access_token(&self) -> &str {
+        match &self {
             AuthToken::EnvVar(token) => token,
             AuthToken::ServiceAccount { token, .. } => token,
             AuthToken::AccessToken { token, .. } => token,
-        };
-        token_as_header(token)
+            AuthToken::Federated { token, .. } => token,
+        }
     }
 
-    fn read_tokens_from_disk() -> Result<AuthToken, AuthError> {
-        let token = fs::read_to_string(CONFIG_DIR.join(ACCESS_TOKEN_PATH))?;
-        let refresh_token = fs::read_to_string(CONFIG_DIR.join(REFRESH_TOKEN_PATH))?;
-        Ok(AuthToken::AccessToken {
-            token,
-            refresh_token,
-        })
+    pub fn to_auth_header(&self) -> String {
+        token_as_header(self.access_token())
     }
 }
+
+impl From<StoredTokens> for AuthToken {
+    fn from(tokens: StoredTokens) -> Self {
+        AuthToken::AccessToken {
+            token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        }
+    }
+}
+
+/// Revoke the stored tokens of `profile` and remove them from the store.
+///
+/// Revoking is best effort, the tokens are removed from the store even if the
+/// auth server could not be reached.
+pub(crate) async fn logout(
+    profile: &Profile,
+    store: &dyn TokenStore,
+    refresh_token: Option<String>,
+    connection_pool: reqwest_middleware::ClientWithMiddleware,
+) -> Result<LogoutResult, AuthError> {
+    // Hold the lock until the tokens are removed, so a concurrent refresh does
+    // not store new tokens after the old ones were revoked.
+    let lock = store.lock().await?;
+    let refresh_token = refresh_token.or_else(|| {
+        store
+            .load()
+            .ok()
+            .flatten()
+            .map(|tokens| tokens.refresh_token)
+    });
+    let revoked = match refresh_token {
+        Some(refresh_token) => {
+            match revoke_refresh_token(profile, &refresh_token, connection_pool).await {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::debug!("failed to revoke refresh token: {e}");
+                    false
+                },
+            }
+        },
+        None => false,
+    };
+
+    store.remove()?;
+    let remaining_credential_source = available_credential_source(profile, store);
+    drop(lock);
+
+    Ok(LogoutResult {
+        revoked,
+        remaining_credential_source,
+    })
+}
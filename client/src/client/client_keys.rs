@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use rcgen::{CertificateParams, DnType, ExtendedKeyUsagePurpose, KeyPair};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::client::Profile;
+use crate::client::token_store::write_private;
+
+/// Directory next to the tokens that holds the client keypairs.
+const CLIENT_KEYS_DIR: &str = "client-keys";
+const CERTIFICATE_EXTENSION: &str = "crt";
+const PRIVATE_KEY_EXTENSION: &str = "key";
+const COMMON_NAME: &str = "polars-cloud-client";
+
+/// Keypair and self-signed certificate the client presents to a direct-mode
+/// cluster, so the cluster only accepts connections from the client that
+/// started it.
+pub(crate) struct ClientKeypair {
+    pub(crate) certificate_pem: String,
+    pub(crate) private_key_pem: String,
+    pub(crate) public_key_pem: String,
+}
+
+impl ClientKeypair {
+    pub(crate) fn generate() -> Result<Self, rcgen::Error> {
+        let key_pair = KeyPair::generate()?;
+        let mut params = CertificateParams::new(Vec::new())?;
+        params
+            .distinguished_name
+            .push(DnType::CommonName, COMMON_NAME);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let certificate = params.self_signed(&key_pair)?;
+
+        Ok(ClientKeypair {
+            certificate_pem: certificate.pem(),
+            private_key_pem: key_pair.serialize_pem(),
+            public_key_pem: key_pair.public_key_pem(),
+        })
+    }
+}
+
+/// Where a keypair is registered: a single cluster, or a manifest whose
+/// clusters all share the keypair.
+pub(crate) enum KeypairOwner<'a> {
+    Cluster(Uuid),
+    Manifest {
+        workspace_id: Uuid,
+        name: &'a str,
+    },
+    /// A keypair that is being registered, stored before the control plane
+    /// knows it and renamed once its owner is known.
+    Pending(&'a ClientKeypair),
+}
+
+impl KeypairOwner<'_> {
+    fn file_stem(&self) -> String {
+        match self {
+            KeypairOwner::Cluster(compute_id) => compute_id.to_string(),
+            KeypairOwner::Manifest { workspace_id, name } => {
+                let name: String = name
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() || c == '-' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect();
+                format!("manifest-{workspace_id}-{name}")
+            },
+            KeypairOwner::Pending(keypair) => {
+                let digest = Sha256::digest(keypair.public_key_pem.as_bytes());
+                let digest: String = digest[..8].iter().map(|b| format!("{b:02x}")).collect();
+                format!("pending-{digest}")
+            },
+        }
+    }
+}
+
+/// Stores the client keypairs of a profile in its token directory, the
+/// private keys only readable by the current user.
+pub(crate) struct ClientKeyStore {
+    dir: PathBuf,
+}
+
+impl ClientKeyStore {
+    pub(crate) fn new(profile: &Profile) -> Self {
+        ClientKeyStore {
+            dir: profile.token_dir().join(CLIENT_KEYS_DIR),
+        }
+    }
+
+    pub(crate) fn paths(&self, owner: &KeypairOwner<'_>) -> (PathBuf, PathBuf) {
+        let stem = owner.file_stem();
+        (
+            self.dir.join(&stem).with_extension(CERTIFICATE_EXTENSION),
+            self.dir.join(&stem).with_extension(PRIVATE_KEY_EXTENSION),
+        )
+    }
+
+    pub(crate) fn load(&self, owner: &KeypairOwner<'_>) -> io::Result<Option<ClientKeypair>> {
+        let (certificate_path, private_key_path) = self.paths(owner);
+        let Some(certificate_pem) = read_if_exists(&certificate_path)? else {
+            return Ok(None);
+        };
+        let Some(private_key_pem) = read_if_exists(&private_key_path)? else {
+            return Ok(None);
+        };
+        let public_key_pem = KeyPair::from_pem(&private_key_pem)
+            .map_err(io::Error::other)?
+            .public_key_pem();
+        Ok(Some(ClientKeypair {
+            certificate_pem,
+            private_key_pem,
+            public_key_pem,
+        }))
+    }
+
+    pub(crate) fn save(&self, owner: &KeypairOwner<'_>, keypair: &ClientKeypair) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let (certificate_path, private_key_path) = self.paths(owner);
+        write_private(&private_key_path, &keypair.private_key_pem)?;
+        write_private(&certificate_path, &keypair.certificate_pem)
+    }
+
+    /// Move the keypair of `from` to `to`, replacing the keypair of `to`.
+    pub(crate) fn rename(&self, from: &KeypairOwner<'_>, to: &KeypairOwner<'_>) -> io::Result<()> {
+        let (from_certificate, from_private_key) = self.paths(from);
+        let (to_certificate, to_private_key) = self.paths(to);
+        fs::rename(from_private_key, to_private_key)?;
+        fs::rename(from_certificate, to_certificate)
+    }
+
+    pub(crate) fn remove(&self, owner: &KeypairOwner<'_>) -> io::Result<()> {
+        let (certificate_path, private_key_path) = self.paths(owner);
+        remove_if_exists(&certificate_path)?;
+        remove_if_exists(&private_key_path)
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn read_if_exists(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -1,17 +1,75 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
 use polars_backend_client::client::user_agent;
 use protos_client_control::{ClientServiceClient, MAX_MESSAGE_LENGTH_CONTROL_PLANE, tonic};
 use protos_common::tonic::Request;
+use protos_common::tonic::codec::CompressionEncoding;
 use protos_common::tonic::service::interceptor::InterceptedService;
 use protos_common::tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use protos_common::{ACCEPTED_COMPRESSION_ENCODINGS, Compression, DEFAULT_COMPRESSION_THRESHOLD};
+use pyo3::pyclass;
 
 use crate::VERSIONS;
-use crate::constants::{API_ADDR, RUNTIME};
+use crate::constants::RUNTIME;
+
+/// Compression of the gRPC messages sent to the control and compute plane.
+///
+/// If the server does not accept the encoding, the message is sent again with
+/// an encoding it does accept, or uncompressed, and that is used from then on.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GrpcCompression {
+    Zstd,
+    /// For servers that do not accept zstd.
+    Gzip,
+}
+
+impl GrpcCompression {
+    pub(crate) fn encoding(self) -> CompressionEncoding {
+        match self {
+            GrpcCompression::Zstd => CompressionEncoding::Zstd,
+            GrpcCompression::Gzip => CompressionEncoding::Gzip,
+        }
+    }
+
+    /// Compression settings, with the default threshold if none is given.
+    pub(crate) fn settings(compression: Option<Self>, threshold: Option<usize>) -> Compression {
+        Compression {
+            encoding: compression.map(Self::encoding),
+            threshold: threshold.unwrap_or(DEFAULT_COMPRESSION_THRESHOLD),
+        }
+    }
+}
+
+impl FromStr for GrpcCompression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zstd" => Ok(GrpcCompression::Zstd),
+            "gzip" => Ok(GrpcCompression::Gzip),
+            _ => Err(format!(
+                "unknown gRPC compression '{s}', expected 'zstd' or 'gzip'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for GrpcCompression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            GrpcCompression::Zstd => write!(f, "zstd"),
+            GrpcCompression::Gzip => write!(f, "gzip"),
+        }
+    }
+}
 
 pub(crate) type ControlPlaneGRPCClient =
     ClientServiceClient<InterceptedService<Channel, fn(Request<()>) -> tonic::Result<Request<()>>>>;
 
-pub(crate) fn get_control_plane_client() -> ControlPlaneGRPCClient {
-    let address = API_ADDR.to_string();
+pub(crate) fn get_control_plane_client(address: &str) -> ControlPlaneGRPCClient {
     let endpoint: Endpoint = format!("{address}:443").parse().unwrap();
     let channel = RUNTIME.0.block_on(async {
         endpoint
@@ -27,9 +85,12 @@ pub(crate) fn get_control_plane_client() -> ControlPlaneGRPCClient {
             .unwrap()
             .connect_lazy()
     });
-    ClientServiceClient::with_interceptor(channel, version_interceptor as _)
-        .max_encoding_message_size(MAX_MESSAGE_LENGTH_CONTROL_PLANE)
-        .max_decoding_message_size(MAX_MESSAGE_LENGTH_CONTROL_PLANE)
+    ACCEPTED_COMPRESSION_ENCODINGS.into_iter().fold(
+        ClientServiceClient::with_interceptor(channel, version_interceptor as _)
+            .max_encoding_message_size(MAX_MESSAGE_LENGTH_CONTROL_PLANE)
+            .max_decoding_message_size(MAX_MESSAGE_LENGTH_CONTROL_PLANE),
+        |client, encoding| client.accept_compressed(encoding),
+    )
 }
 
 #[allow(clippy::result_large_err)]
@@ -1,33 +1,317 @@
-use std::thread::sleep;
 use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use pyo3::{Py, PyAny, Python, pyclass};
+use rand::RngCore;
+use reqwest::Url;
 use serde::Deserialize;
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
 
-use crate::client::AuthToken;
-use crate::client::utils::{Tokens, write_tokens};
-use crate::constants::{AUTH_DOMAIN, LOGIN_AUDIENCE, LOGIN_CLIENT_ID};
+use crate::client::token_store::{StoredTokens, TokenStore};
+use crate::client::utils::Tokens;
+use crate::client::{AuthToken, Profile};
+use crate::constants::{LOGIN_AUDIENCE, LOGIN_CLIENT_ID};
 use crate::error::ApiError;
 
+/// Path the auth server redirects to on the loopback listener.
+const REDIRECT_PATH: &str = "/callback";
+
+/// How long the loopback listener waits for the browser to complete the login.
+const BROWSER_LOGIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How long a connection to the loopback listener may take to send its request.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const LOGIN_SUCCESS_PAGE: &str = "<html><body><h3>Login successful</h3>\
+    <p>You can close this window and return to Polars Cloud.</p></body></html>";
+const LOGIN_FAILURE_PAGE: &str = "<html><body><h3>Login failed</h3>\
+    <p>Please return to Polars Cloud and try again.</p></body></html>";
+
+/// Increase of the polling interval when the auth server asks to slow down.
+const SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
 #[derive(Deserialize)]
 struct AuthResponse {
     device_code: String,
     user_code: String,
     verification_uri_complete: String,
     expires_in: u64,
+    #[serde(default = "default_interval")]
     interval: u64,
 }
 
+fn default_interval() -> u64 {
+    5
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Progress of a device login, passed to the progress callback of `login`.
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct DeviceLoginProgress {
+    /// One of `waiting`, `slow_down` or `approved`.
+    status: String,
+    verification_uri: String,
+    user_code: String,
+    /// Seconds until the login code expires.
+    expires_in_secs: u64,
+}
+
+fn login_error(message: String) -> ApiError {
+    ApiError::AuthLoadError(anyhow!(message).into())
+}
+
+fn openid_connect_url(profile: &Profile, endpoint: &str) -> String {
+    format!(
+        "https://{}/realms/Polars/protocol/openid-connect/{endpoint}",
+        profile.auth_domain()
+    )
+}
+
+/// Whether a browser on this machine can complete a login.
+///
+/// Over SSH a browser would open on the remote machine, which can not reach
+/// the loopback listener of the user, so the device flow is used instead.
+fn can_open_browser() -> bool {
+    std::env::var_os("SSH_CONNECTION").is_none()
+        && std::env::var_os("SSH_TTY").is_none()
+        && webbrowser::Browser::is_available()
+}
+
+/// Log in interactively.
+///
+/// Uses the authorization code flow with PKCE when a browser can be opened
+/// and falls back to the device flow otherwise, or when the browser login
+/// cannot receive the redirect. The `progress` callback is only called during
+/// a device login.
 #[allow(clippy::result_large_err)]
 pub async fn login_new(
+    profile: &Profile,
+    store: &dyn TokenStore,
     connection_pool: reqwest_middleware::ClientWithMiddleware,
+    progress: Option<&Py<PyAny>>,
 ) -> Result<AuthToken, ApiError> {
+    let tokens = if can_open_browser() {
+        login_with_browser(profile, connection_pool.clone()).await?
+    } else {
+        None
+    };
+    let tokens = match tokens {
+        Some(tokens) => tokens,
+        None => login_with_device_code(profile, connection_pool, progress).await?,
+    };
+
+    let tokens = StoredTokens {
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+    };
+    let saved = match store.lock().await {
+        Ok(_lock) => store.save(&tokens),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
+        tracing::debug!("failed to store tokens: {e}");
+    }
+    Ok(AuthToken::from(tokens))
+}
+
+fn random_string(n_bytes: usize) -> String {
+    let mut bytes = vec![0u8; n_bytes];
+    rand::rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE code verifier and its S256 challenge (RFC 7636).
+fn pkce_pair() -> (String, String) {
+    let verifier = random_string(32);
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+/// Log in with the authorization code flow and PKCE.
+///
+/// Returns `None` if the browser could not be opened, the loopback listener
+/// for the redirect could not be started or the redirect did not arrive in time.
+#[allow(clippy::result_large_err)]
+async fn login_with_browser(
+    profile: &Profile,
+    connection_pool: reqwest_middleware::ClientWithMiddleware,
+) -> Result<Option<Tokens>, ApiError> {
+    let bound = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .and_then(|listener| Ok((listener.local_addr()?.port(), listener)));
+    let (port, listener) = match bound {
+        Ok(bound) => bound,
+        Err(e) => {
+            tracing::debug!(
+                "failed to start the login listener, falling back to the device flow: {e}"
+            );
+            return Ok(None);
+        },
+    };
+    let redirect_uri = format!("http://127.0.0.1:{port}{REDIRECT_PATH}");
+
+    let (verifier, challenge) = pkce_pair();
+    let state = random_string(16);
+    let mut authorize_url = Url::parse(&openid_connect_url(profile, "auth"))
+        .map_err(|e| login_error(format!("Invalid auth server address: {e}")))?;
+    authorize_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", LOGIN_CLIENT_ID)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", "openid")
+        .append_pair("audience", LOGIN_AUDIENCE)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256");
+
+    if let Err(e) = webbrowser::open(authorize_url.as_str()) {
+        tracing::debug!("failed to open browser, falling back to the device flow: {e}");
+        return Ok(None);
+    }
+    println!("Please complete the login process in your browser.");
+    println!("If your browser did not open automatically, please go to the URL: {authorize_url}");
+
+    let Ok(code) = tokio::time::timeout(
+        BROWSER_LOGIN_TIMEOUT,
+        receive_authorization_code(&listener, &state),
+    )
+    .await
+    else {
+        println!("The browser login has timed out, falling back to logging in with a device code.");
+        return Ok(None);
+    };
+    let code = code?;
+
+    let tokens = connection_pool
+        .post(openid_connect_url(profile, "token"))
+        .form(&json!({
+            "client_id": LOGIN_CLIENT_ID,
+            "grant_type": "authorization_code",
+            "code": code,
+            "redirect_uri": redirect_uri,
+            "code_verifier": verifier,
+        }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Tokens>()
+        .await?;
+    Ok(Some(tokens))
+}
+
+/// Wait for the redirect of the auth server and return the authorization code.
+///
+/// Every connection is answered in its own task, so idle connections, such as
+/// browser preconnects, do not hold up the redirect. Requests for other paths or
+/// with another `state` are answered with an error and otherwise ignored.
+#[allow(clippy::result_large_err)]
+async fn receive_authorization_code(
+    listener: &TcpListener,
+    state: &str,
+) -> Result<String, ApiError> {
+    let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) =
+                    accepted.map_err(|e| login_error(format!("Login listener failed: {e}")))?;
+                let sender = sender.clone();
+                let state = state.to_string();
+                tokio::spawn(async move {
+                    if let Some(result) = handle_login_request(stream, &state).await {
+                        let _ = sender.send(result).await;
+                    }
+                });
+            },
+            Some(result) = receiver.recv() => return result,
+        }
+    }
+}
+
+/// Answer a request on the loopback listener, returning the outcome of the
+/// login if it is the redirect of the auth server for `state`.
+#[allow(clippy::result_large_err)]
+async fn handle_login_request(
+    mut stream: TcpStream,
+    state: &str,
+) -> Option<Result<String, ApiError>> {
+    let url = tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request_url(&mut stream))
+        .await
+        .ok()??;
+    if url.path() != REDIRECT_PATH {
+        let _ = respond(&mut stream, "404 Not Found", "").await;
+        return None;
+    }
+
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if param("state").as_deref() != Some(state) {
+        let _ = respond(&mut stream, "400 Bad Request", "").await;
+        return None;
+    }
+    let result = if let Some(error) = param("error") {
+        let description = param("error_description").unwrap_or_default();
+        Err(login_error(format!("Login failed: {error} {description}")))
+    } else {
+        param("code").ok_or_else(|| {
+            login_error("Login failed: no authorization code was returned".to_string())
+        })
+    };
+
+    let page = if result.is_ok() {
+        LOGIN_SUCCESS_PAGE
+    } else {
+        LOGIN_FAILURE_PAGE
+    };
+    let _ = respond(&mut stream, "200 OK", page).await;
+    Some(result)
+}
+
+/// Read the request line of an HTTP request and return its URL.
+async fn read_request_url(stream: &mut TcpStream) -> Option<Url> {
+    let mut request_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut request_line)
+        .await
+        .ok()?;
+    let target = request_line.split_whitespace().nth(1)?;
+    Url::parse("http://127.0.0.1").ok()?.join(target).ok()
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Log in with the device authorization flow (RFC 8628).
+#[allow(clippy::result_large_err)]
+async fn login_with_device_code(
+    profile: &Profile,
+    connection_pool: reqwest_middleware::ClientWithMiddleware,
+    progress: Option<&Py<PyAny>>,
+) -> Result<Tokens, ApiError> {
     let device: AuthResponse = connection_pool
-        .post(format!(
-            "https://{}/realms/Polars/protocol/openid-connect/auth/device",
-            *AUTH_DOMAIN
-        ))
+        .post(openid_connect_url(profile, "auth/device"))
         .form(&json!({"client_id": LOGIN_CLIENT_ID, "audience": LOGIN_AUDIENCE}))
         .send()
         .await?
@@ -35,7 +319,9 @@ pub async fn login_new(
         .await?;
 
     // Allow user to give us permission
-    webbrowser::open(&device.verification_uri_complete).unwrap();
+    if can_open_browser() {
+        let _ = webbrowser::open(&device.verification_uri_complete);
+    }
     println!("Please complete the login process in your browser.");
     println!(
         "If your browser did not open automatically, please go to the URL: {}",
@@ -43,11 +329,24 @@ pub async fn login_new(
     );
     println!("Your login code is: {}", device.user_code);
 
+    let deadline = Instant::now() + Duration::from_secs(device.expires_in);
+    let report = |status: &str| -> Result<(), ApiError> {
+        let Some(progress) = progress else {
+            return Ok(());
+        };
+        let update = DeviceLoginProgress {
+            status: status.to_string(),
+            verification_uri: device.verification_uri_complete.clone(),
+            user_code: device.user_code.clone(),
+            expires_in_secs: deadline.saturating_duration_since(Instant::now()).as_secs(),
+        };
+        Python::attach(|py| progress.call1(py, (update,)))?;
+        Ok(())
+    };
+    report("waiting")?;
+
     // Get token with device code
-    let url = format!(
-        "https://{}/realms/Polars/protocol/openid-connect/token",
-        *AUTH_DOMAIN
-    );
+    let url = openid_connect_url(profile, "token");
 
     let data = json!({
         "client_id": LOGIN_CLIENT_ID,
@@ -55,27 +354,49 @@ pub async fn login_new(
         "grant_type": "urn:ietf:params:oauth:grant-type:device_code",
     });
 
-    let start_time = Instant::now();
-    let mut last_check = Instant::now();
-    while start_time.elapsed() < Duration::from_secs(device.expires_in) {
-        if last_check.elapsed() < Duration::from_secs(device.interval) {
-            sleep(Duration::from_millis(50));
-            continue;
+    let mut interval = Duration::from_secs(device.interval);
+    loop {
+        tokio::time::sleep(interval).await;
+        if Instant::now() >= deadline {
+            return Err(login_error(
+                "The login code has expired, Please try again".to_string(),
+            ));
         }
-        last_check = Instant::now();
 
         let response = connection_pool.post(url.clone()).form(&data).send().await?;
-
-        if response.status().is_success() {
+        let status = response.status();
+        if status.is_success() {
             let tokens = response.json::<Tokens>().await.map_err(ApiError::from)?;
-            let _ = write_tokens(&tokens.access_token, &tokens.refresh_token);
-            return Ok(AuthToken::AccessToken {
-                token: tokens.access_token,
-                refresh_token: tokens.refresh_token,
-            });
+            report("approved")?;
+            return Ok(tokens);
+        }
+
+        let error = response
+            .json::<TokenErrorResponse>()
+            .await
+            .map_err(|_| login_error(format!("Logging in failed with status {status}")))?;
+        match error.error.as_str() {
+            "authorization_pending" => report("waiting")?,
+            "slow_down" => {
+                interval += SLOW_DOWN_INCREMENT;
+                report("slow_down")?
+            },
+            "access_denied" => {
+                return Err(login_error(
+                    "The login request was denied in the browser".to_string(),
+                ));
+            },
+            "expired_token" => {
+                return Err(login_error(
+                    "The login code has expired, Please try again".to_string(),
+                ));
+            },
+            other => {
+                return Err(login_error(format!(
+                    "Logging in failed with: {other} {}",
+                    error.error_description.unwrap_or_default()
+                )));
+            },
         }
     }
-    Err(ApiError::AuthLoadError(
-        anyhow!("Logging in has timed out, Please try again").into(),
-    ))
 }
@@ -1,12 +1,22 @@
 mod api_client;
 mod auth;
+mod client_keys;
 mod error;
 mod grpc;
+mod jwt;
 mod login;
+mod profile;
+mod token_manager;
+mod token_store;
 mod utils;
 
 pub(crate) use api_client::WrappedAPIClient;
-pub(crate) use auth::{AuthMethod, AuthToken};
+pub(crate) use auth::{AuthMethod, AuthToken, LogoutResult};
+pub(crate) use client_keys::{ClientKeyStore, ClientKeypair, KeypairOwner};
 pub use error::AuthError;
-pub(crate) use grpc::ControlPlaneGRPCClient;
-pub(crate) use utils::{polars_version, py_is_token_expired, python_version};
+pub(crate) use grpc::{ControlPlaneGRPCClient, GrpcCompression};
+pub(crate) use jwt::TokenClaims;
+pub(crate) use login::DeviceLoginProgress;
+pub(crate) use profile::{CredentialSource, Profile};
+pub(crate) use token_manager::TokenManager;
+pub(crate) use utils::{is_token_expired, polars_version, py_is_token_expired, python_version};
@@ -0,0 +1,292 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use ini::Ini;
+use protos_common::Compression;
+use pyo3::exceptions::PyValueError;
+use pyo3::{PyResult, pyclass};
+
+use crate::client::GrpcCompression;
+use crate::constants::{
+    ACCESS_TOKEN_PATH, CLIENT_ID_ENV, CLIENT_SECRET_FILE_ENV, CONFIG_DIR, CONFIG_FILE_ENV,
+    CONFIG_FILE_PATH, CREDENTIAL_PROCESS_ENV, DEFAULT_DOMAIN, DEFAULT_PROFILE,
+    FEDERATED_TOKEN_FILE_ENV, FEDERATED_TOKEN_ISSUER_ENV, GRPC_COMPRESSION_ENV,
+    GRPC_COMPRESSION_THRESHOLD_ENV, PROFILE_ENV, PROFILES_DIR, REFRESH_TOKEN_PATH, TOKENS_PATH,
+};
+
+/// Where the credentials of a profile are taken from.
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// An access token in the `POLARS_CLOUD_ACCESS_TOKEN` environment variable.
+    Environment,
+    /// Service account client id and secret.
+    ServiceAccount,
+    /// Tokens stored by an interactive login.
+    Login,
+    /// An external OIDC token of the workload, exchanged for a Polars Cloud token.
+    Federated,
+}
+
+impl FromStr for CredentialSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "environment" => Ok(CredentialSource::Environment),
+            "service_account" => Ok(CredentialSource::ServiceAccount),
+            "login" => Ok(CredentialSource::Login),
+            "federated" => Ok(CredentialSource::Federated),
+            _ => Err(format!(
+                "unknown credential source '{s}', expected one of 'environment', 'service_account', 'login' or 'federated'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::Environment => write!(f, "environment"),
+            CredentialSource::ServiceAccount => write!(f, "service_account"),
+            CredentialSource::Login => write!(f, "login"),
+            CredentialSource::Federated => write!(f, "federated"),
+        }
+    }
+}
+
+/// A named set of settings from the Polars Cloud config file.
+///
+/// The config file follows the layout of `~/.aws/config`:
+///
+/// ```ini
+/// [default]
+/// workspace = my-workspace
+///
+/// [profile staging]
+/// domain = stg.cloud.pola.rs
+/// organization = my-organization
+/// credential_source = login
+///
+/// [profile batch]
+/// client_id = my-service-account
+/// credential_process = vault-polars-cloud --role batch
+///
+/// [profile ci]
+/// credential_source = federated
+/// federated_token_file = /var/run/secrets/tokens/polars-cloud
+///
+/// [profile etl]
+/// grpc_compression = zstd
+/// grpc_compression_threshold = 65536
+/// ```
+///
+/// Settings that are missing from the profile fall back to the environment
+/// variables and finally to the built-in defaults.
+#[pyclass(get_all)]
+#[derive(Clone, Debug)]
+pub struct Profile {
+    name: String,
+    domain: String,
+    api_domain_prefix: String,
+    organization: Option<String>,
+    workspace: Option<String>,
+    credential_source: Option<CredentialSource>,
+    client_id: Option<String>,
+    /// File holding the client secret of the service account.
+    client_secret_file: Option<String>,
+    /// Command that prints the service account credentials as JSON.
+    credential_process: Option<String>,
+    /// File holding the external OIDC token for workload identity federation.
+    federated_token_file: Option<String>,
+    /// Alias of the identity provider that issued the external OIDC token.
+    federated_token_issuer: Option<String>,
+    /// Compression of the gRPC messages sent to the control plane.
+    grpc_compression: Option<GrpcCompression>,
+    /// Messages smaller than this many bytes are sent uncompressed.
+    grpc_compression_threshold: Option<usize>,
+}
+
+impl Profile {
+    /// Load the profile with the given name, `POLARS_CLOUD_PROFILE` or the default profile.
+    pub(crate) fn load(name: Option<String>) -> PyResult<Self> {
+        let name = name
+            .or_else(|| std::env::var(PROFILE_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(PyValueError::new_err(format!(
+                "Invalid profile name '{name}', only letters, digits, '-' and '_' are allowed"
+            )));
+        }
+
+        let path = config_file_path();
+        let config = if path.exists() {
+            Ini::load_from_file(&path).map_err(|e| {
+                PyValueError::new_err(format!(
+                    "Failed to read config file {}: {e}",
+                    path.display()
+                ))
+            })?
+        } else {
+            Ini::new()
+        };
+
+        let section = if name == DEFAULT_PROFILE {
+            config
+                .section(Some(DEFAULT_PROFILE))
+                .or_else(|| config.section(Some(format!("profile {DEFAULT_PROFILE}"))))
+        } else {
+            let section = config.section(Some(format!("profile {name}")));
+            if section.is_none() {
+                return Err(PyValueError::new_err(format!(
+                    "Profile '{name}' does not exist in config file {}",
+                    path.display()
+                )));
+            }
+            section
+        };
+        let get = |key: &str| section.and_then(|s| s.get(key)).map(str::to_string);
+
+        let credential_source = get("credential_source")
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Profile '{name}': {e}")))?;
+        let grpc_compression = get("grpc_compression")
+            .or_else(|| std::env::var(GRPC_COMPRESSION_ENV).ok())
+            .filter(|s| s != "none")
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Profile '{name}': {e}")))?;
+        let grpc_compression_threshold = get("grpc_compression_threshold")
+            .or_else(|| std::env::var(GRPC_COMPRESSION_THRESHOLD_ENV).ok())
+            .map(|s| {
+                s.parse().map_err(|_| {
+                    PyValueError::new_err(format!(
+                        "Profile '{name}': invalid gRPC compression threshold '{s}'"
+                    ))
+                })
+            })
+            .transpose()?;
+
+        // The service account of a profile is taken as a whole, the environment
+        // never completes it with the id or secret of another account.
+        let has_service_account = ["client_id", "client_secret_file", "credential_process"]
+            .into_iter()
+            .any(|key| get(key).is_some());
+        let service_account_env = |var: &str| {
+            if has_service_account {
+                None
+            } else {
+                std::env::var(var).ok()
+            }
+        };
+
+        Ok(Profile {
+            domain: get("domain")
+                .or_else(|| std::env::var("POLARS_CLOUD_DOMAIN").ok())
+                .unwrap_or_else(|| DEFAULT_DOMAIN.to_string()),
+            api_domain_prefix: get("api_domain_prefix")
+                .or_else(|| std::env::var("POLARS_CLOUD_API_DOMAIN_PREFIX").ok())
+                .unwrap_or_else(|| "api".to_string()),
+            organization: get("organization"),
+            workspace: get("workspace"),
+            credential_source,
+            client_id: get("client_id").or_else(|| service_account_env(CLIENT_ID_ENV)),
+            client_secret_file: get("client_secret_file")
+                .or_else(|| service_account_env(CLIENT_SECRET_FILE_ENV)),
+            credential_process: get("credential_process")
+                .or_else(|| service_account_env(CREDENTIAL_PROCESS_ENV)),
+            federated_token_file: get("federated_token_file")
+                .or_else(|| std::env::var(FEDERATED_TOKEN_FILE_ENV).ok()),
+            federated_token_issuer: get("federated_token_issuer")
+                .or_else(|| std::env::var(FEDERATED_TOKEN_ISSUER_ENV).ok()),
+            grpc_compression,
+            grpc_compression_threshold,
+            name,
+        })
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub(crate) fn credential_source(&self) -> Option<CredentialSource> {
+        self.credential_source
+    }
+
+    pub(crate) fn client_id(&self) -> Option<&str> {
+        self.client_id.as_deref()
+    }
+
+    pub(crate) fn client_secret_file(&self) -> Option<&str> {
+        self.client_secret_file.as_deref()
+    }
+
+    pub(crate) fn credential_process(&self) -> Option<&str> {
+        self.credential_process.as_deref()
+    }
+
+    pub(crate) fn federated_token_file(&self) -> Option<&str> {
+        self.federated_token_file.as_deref()
+    }
+
+    pub(crate) fn federated_token_issuer(&self) -> Option<&str> {
+        self.federated_token_issuer.as_deref()
+    }
+
+    /// Compression of the gRPC messages sent to the control plane.
+    pub(crate) fn grpc_compression(&self) -> Compression {
+        GrpcCompression::settings(self.grpc_compression, self.grpc_compression_threshold)
+    }
+
+    pub(crate) fn auth_domain(&self) -> String {
+        format!("auth.{}", self.domain)
+    }
+
+    pub(crate) fn api_addr(&self) -> String {
+        format!("https://{}.{}", self.api_domain_prefix, self.domain)
+    }
+
+    /// Directory holding the tokens of this profile.
+    ///
+    /// The default profile keeps using the config directory itself, so tokens
+    /// stored before profiles existed remain valid.
+    pub(crate) fn token_dir(&self) -> PathBuf {
+        if self.name == DEFAULT_PROFILE {
+            CONFIG_DIR.clone()
+        } else {
+            CONFIG_DIR.join(PROFILES_DIR).join(&self.name)
+        }
+    }
+
+    /// File holding both tokens of an interactive login.
+    pub(crate) fn tokens_path(&self) -> PathBuf {
+        self.token_dir().join(TOKENS_PATH)
+    }
+
+    /// Access token file of logins stored before both tokens shared a file.
+    pub(crate) fn access_token_path(&self) -> PathBuf {
+        self.token_dir().join(ACCESS_TOKEN_PATH)
+    }
+
+    /// Refresh token file of logins stored before both tokens shared a file.
+    pub(crate) fn refresh_token_path(&self) -> PathBuf {
+        self.token_dir().join(REFRESH_TOKEN_PATH)
+    }
+}
+
+fn config_file_path() -> PathBuf {
+    std::env::var(CONFIG_FILE_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|_e| CONFIG_DIR.join(CONFIG_FILE_PATH))
+}
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use http::Extensions;
+use reqwest::header::{AUTHORIZATION, HeaderValue};
+use reqwest::{Request, Response, StatusCode};
+use reqwest_middleware::{ClientWithMiddleware, Middleware, Next};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::client::auth::{LogoutResult, logout};
+use crate::client::jwt::{JwksCache, TokenClaims};
+use crate::client::token_store::TokenStore;
+use crate::client::{AuthError, AuthMethod, AuthToken, CredentialSource, Profile};
+
+/// Tokens that expire within this margin are refreshed before they are used.
+pub(crate) const DEFAULT_TOKEN_REFRESH_SKEW: Duration = Duration::from_secs(60);
+
+/// Holds the credentials of a client and refreshes them ahead of expiry.
+///
+/// The token sits behind an async mutex that stays locked while refreshing, so
+/// concurrent requests wait for a single refresh instead of each starting one.
+#[derive(Clone)]
+pub(crate) struct TokenManager {
+    inner: Arc<TokenManagerInner>,
+}
+
+struct TokenManagerInner {
+    profile: Profile,
+    store: Arc<dyn TokenStore>,
+    /// Connection pool for the auth server, without the [`AuthMiddleware`].
+    connection_pool: ClientWithMiddleware,
+    refresh_skew: Duration,
+    jwks: JwksCache,
+    token: Mutex<Option<AuthToken>>,
+}
+
+impl TokenManager {
+    pub(crate) fn new(
+        profile: Profile,
+        store: Arc<dyn TokenStore>,
+        connection_pool: ClientWithMiddleware,
+        refresh_skew: Duration,
+    ) -> Self {
+        TokenManager {
+            inner: Arc::new(TokenManagerInner {
+                jwks: JwksCache::new(&profile),
+                profile,
+                store,
+                connection_pool,
+                refresh_skew,
+                token: Mutex::new(None),
+            }),
+        }
+    }
+
+    pub(crate) fn profile(&self) -> &Profile {
+        &self.inner.profile
+    }
+
+    pub(crate) fn store(&self) -> &dyn TokenStore {
+        self.inner.store.as_ref()
+    }
+
+    pub(crate) fn connection_pool(&self) -> ClientWithMiddleware {
+        self.inner.connection_pool.clone()
+    }
+
+    /// Auth header of a valid token, loading or refreshing the token when needed.
+    ///
+    /// Every new token is validated against the key set of the auth server, so
+    /// a token of another environment is rejected before it is sent anywhere.
+    pub(crate) async fn auth_header(&self) -> Result<String, AuthError> {
+        let inner = &self.inner;
+        let mut token = inner.token.lock().await;
+        let previous = token.as_ref().map(|t| t.access_token().to_string());
+        match token.as_mut() {
+            Some(token) => {
+                token
+                    .refresh(
+                        &inner.profile,
+                        inner.store.as_ref(),
+                        inner.connection_pool.clone(),
+                        inner.refresh_skew,
+                    )
+                    .await?
+            },
+            None => {
+                *token = Some(
+                    AuthToken::new(
+                        &inner.profile,
+                        inner.store.as_ref(),
+                        inner.connection_pool.clone(),
+                        inner.refresh_skew,
+                    )
+                    .await?,
+                )
+            },
+        }
+        let token = token.as_ref().unwrap();
+        if previous.as_deref() != Some(token.access_token()) {
+            inner
+                .jwks
+                .validate(token, &inner.profile, &inner.connection_pool)
+                .await?;
+        }
+        Ok(token.to_auth_header())
+    }
+
+    /// Claims of the current token, validated against the key set of the auth server.
+    pub(crate) async fn claims(&self) -> Result<TokenClaims, AuthError> {
+        self.auth_header().await?;
+        let inner = &self.inner;
+        let token = inner.token.lock().await;
+        let token = token
+            .as_ref()
+            .ok_or_else(|| AuthError::new("Not authenticated."))?;
+        inner
+            .jwks
+            .validate(token, &inner.profile, &inner.connection_pool)
+            .await
+    }
+
+    /// Renew the token after the server rejected the `rejected` auth header.
+    ///
+    /// Returns `None` if there is no token that can be renewed. If a concurrent
+    /// request already replaced the rejected token, the new one is returned as is.
+    pub(crate) async fn renew_rejected(&self, rejected: &str) -> Result<Option<String>, AuthError> {
+        let inner = &self.inner;
+        let mut token = inner.token.lock().await;
+        let Some(token) = token.as_mut() else {
+            return Ok(None);
+        };
+        if token.to_auth_header() == rejected {
+            if !token.can_renew() {
+                return Ok(None);
+            }
+            tracing::debug!("token was rejected, renewing");
+            token
+                .renew(
+                    &inner.profile,
+                    inner.store.as_ref(),
+                    inner.connection_pool.clone(),
+                    inner.refresh_skew,
+                )
+                .await?;
+        }
+        Ok(Some(token.to_auth_header()))
+    }
+
+    /// Forget the current token and revoke and remove the stored tokens of the profile.
+    pub(crate) async fn logout(&self) -> Result<LogoutResult, AuthError> {
+        let inner = &self.inner;
+        let mut token = inner.token.lock().await;
+        let refresh_token = match token.take() {
+            Some(AuthToken::AccessToken { refresh_token, .. }) => Some(refresh_token),
+            _ => None,
+        };
+        logout(
+            &inner.profile,
+            inner.store.as_ref(),
+            refresh_token,
+            inner.connection_pool.clone(),
+        )
+        .await
+    }
+
+    pub(crate) async fn set(&self, token: AuthToken) {
+        *self.inner.token.lock().await = Some(token);
+    }
+
+    pub(crate) async fn clear(&self) {
+        *self.inner.token.lock().await = None;
+    }
+
+    pub(crate) async fn method(&self) -> Option<AuthMethod> {
+        self.inner
+            .token
+            .lock()
+            .await
+            .as_ref()
+            .map(|token| token.method())
+    }
+
+    pub(crate) async fn credential_source(&self) -> Option<CredentialSource> {
+        self.inner
+            .token
+            .lock()
+            .await
+            .as_ref()
+            .map(|token| token.credential_source())
+    }
+
+    /// Short hash of the access token, to tell tokens apart without revealing them.
+    pub(crate) async fn token_fingerprint(&self) -> Option<String> {
+        self.inner.token.lock().await.as_ref().map(|token| {
+            let digest = Sha256::digest(token.access_token().as_bytes());
+            digest[..6].iter().map(|b| format!("{b:02x}")).collect()
+        })
+    }
+}
+
+/// Sets the auth header of every request from the [`TokenManager`].
+///
+/// A request that is rejected with `401 Unauthorized` is retried once with a
+/// renewed token.
+pub(crate) struct AuthMiddleware(pub(crate) TokenManager);
+
+fn header_value(header: &str) -> reqwest_middleware::Result<HeaderValue> {
+    HeaderValue::from_str(header).map_err(|e| reqwest_middleware::Error::Middleware(e.into()))
+}
+
+#[async_trait::async_trait]
+impl Middleware for AuthMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let header = self
+            .0
+            .auth_header()
+            .await
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?;
+        req.headers_mut()
+            .insert(AUTHORIZATION, header_value(&header)?);
+
+        let retry = req.try_clone();
+        let response = next.clone().run(req, extensions).await?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let Some(mut retry) = retry else {
+            return Ok(response);
+        };
+        let Some(renewed) = self
+            .0
+            .renew_rejected(&header)
+            .await
+            .map_err(|e| reqwest_middleware::Error::Middleware(e.into()))?
+        else {
+            return Ok(response);
+        };
+        retry
+            .headers_mut()
+            .insert(AUTHORIZATION, header_value(&renewed)?);
+        next.run(retry, extensions).await
+    }
+}
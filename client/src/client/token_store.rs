@@ -0,0 +1,239 @@
+use std::any::Any;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::{AuthError, Profile};
+
+/// File next to the tokens that is locked while they are refreshed.
+const LOCK_FILE: &str = ".token.lock";
+
+/// The tokens of an interactive login.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StoredTokens {
+    pub(crate) access_token: String,
+    pub(crate) refresh_token: String,
+}
+
+/// Persistent storage for the tokens of an interactive login.
+///
+/// Several processes may share a store, so a refresh holds the lock of the
+/// store and first checks whether another process already stored new tokens.
+#[async_trait::async_trait]
+pub(crate) trait TokenStore: Send + Sync {
+    /// The stored tokens, or `None` if there are none.
+    ///
+    /// Must be called under [`TokenStore::lock`].
+    fn load(&self) -> Result<Option<StoredTokens>, AuthError>;
+
+    /// Replace the stored tokens, must be called under [`TokenStore::lock`].
+    fn save(&self, tokens: &StoredTokens) -> Result<(), AuthError>;
+
+    /// Remove the stored tokens, it is not an error if there are none.
+    ///
+    /// Must be called under [`TokenStore::lock`].
+    fn remove(&self) -> Result<(), AuthError>;
+
+    /// Lock the store across processes until the returned guard is dropped.
+    ///
+    /// Stores that are not shared between processes need not lock.
+    async fn lock(&self) -> Result<Box<dyn Any + Send>, AuthError> {
+        Ok(Box::new(()))
+    }
+}
+
+/// Stores the tokens of a profile as a single file in its token directory.
+///
+/// The file is only readable by the current user and is replaced atomically,
+/// so a reader never sees an access token without its refresh token.
+pub(crate) struct FileTokenStore {
+    dir: PathBuf,
+    tokens_path: PathBuf,
+    /// Token files of logins stored before both tokens shared a file, still
+    /// read until the next save.
+    legacy_paths: [PathBuf; 2],
+}
+
+impl FileTokenStore {
+    pub(crate) fn new(profile: &Profile) -> Self {
+        FileTokenStore {
+            dir: profile.token_dir(),
+            tokens_path: profile.tokens_path(),
+            legacy_paths: [profile.access_token_path(), profile.refresh_token_path()],
+        }
+    }
+
+    fn load_legacy(&self) -> Result<Option<StoredTokens>, AuthError> {
+        let [access_token_path, refresh_token_path] = &self.legacy_paths;
+        let Some(access_token) = read_if_exists(access_token_path)? else {
+            return Ok(None);
+        };
+        let Some(refresh_token) = read_if_exists(refresh_token_path)? else {
+            return Ok(None);
+        };
+        Ok(Some(StoredTokens {
+            access_token,
+            refresh_token,
+        }))
+    }
+}
+
+fn store_error(action: &str, path: &Path, e: io::Error) -> AuthError {
+    AuthError::new(&format!(
+        "Failed to {action} the stored token {}: {e}.",
+        path.display()
+    ))
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>, AuthError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(store_error("read", path, e)),
+    }
+}
+
+/// Write `contents` to a temporary file only the current user can read and
+/// rename it over `path`.
+pub(super) fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let _ = fs::remove_file(&tmp_path);
+    let result = options.open(&tmp_path).and_then(|mut file| {
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+#[async_trait::async_trait]
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<StoredTokens>, AuthError> {
+        let Some(contents) = read_if_exists(&self.tokens_path)? else {
+            return self.load_legacy();
+        };
+        serde_json::from_str(&contents).map(Some).map_err(|e| {
+            store_error(
+                "parse",
+                &self.tokens_path,
+                io::Error::new(io::ErrorKind::InvalidData, e),
+            )
+        })
+    }
+
+    fn save(&self, tokens: &StoredTokens) -> Result<(), AuthError> {
+        fs::create_dir_all(&self.dir).map_err(|e| store_error("create", &self.dir, e))?;
+        let contents = serde_json::to_string(tokens).unwrap();
+        write_private(&self.tokens_path, &contents)
+            .map_err(|e| store_error("write", &self.tokens_path, e))?;
+        // The legacy files would hold tokens that were just rotated out
+        for path in &self.legacy_paths {
+            if let Err(e) = fs::remove_file(path)
+                && e.kind() != io::ErrorKind::NotFound
+            {
+                tracing::debug!("failed to remove legacy token {}: {e}", path.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&self) -> Result<(), AuthError> {
+        for path in std::iter::once(&self.tokens_path).chain(&self.legacy_paths) {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    return Err(store_error("remove", path, e));
+                },
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+
+    async fn lock(&self) -> Result<Box<dyn Any + Send>, AuthError> {
+        let dir = self.dir.clone();
+        let path = dir.join(LOCK_FILE);
+        tokio::task::spawn_blocking(move || {
+            fs::create_dir_all(&dir)?;
+            let file = File::options()
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            file.lock()?;
+            Ok(Box::new(file) as Box<dyn Any + Send>)
+        })
+        .await
+        .map_err(|e| AuthError::new(&format!("Failed to lock the stored tokens: {e}.")))?
+        .map_err(|e: io::Error| AuthError::new(&format!("Failed to lock the stored tokens: {e}.")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_token_store() {
+        let dir = std::env::temp_dir().join(format!("polars-cloud-tokens-{}", std::process::id()));
+        let store = FileTokenStore {
+            tokens_path: dir.join("tokens.json"),
+            legacy_paths: [dir.join("access"), dir.join("refresh")],
+            dir: dir.clone(),
+        };
+        let lock = store.lock().await.unwrap();
+        assert_eq!(store.load().unwrap(), None);
+
+        // Logins stored in separate files are read until the next save
+        fs::create_dir_all(&dir).unwrap();
+        write_private(&dir.join("access"), "access-0").unwrap();
+        write_private(&dir.join("refresh"), "refresh-0").unwrap();
+        assert_eq!(
+            store.load().unwrap().map(|tokens| tokens.access_token),
+            Some("access-0".to_string())
+        );
+        drop(lock);
+
+        let tokens = StoredTokens {
+            access_token: "access-1".to_string(),
+            refresh_token: "refresh-1".to_string(),
+        };
+        let lock = store.lock().await.unwrap();
+        store.save(&tokens).unwrap();
+        assert_eq!(store.load().unwrap(), Some(tokens));
+        assert!(!dir.join("access").exists());
+        drop(lock);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join("tokens.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let lock = store.lock().await.unwrap();
+        store.remove().unwrap();
+        store.remove().unwrap();
+        assert_eq!(store.load().unwrap(), None);
+        drop(lock);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -1,8 +1,7 @@
-use std::fs;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use base64::Engine;
-use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use polars_axum_models::PythonVersion;
 use pyo3::exceptions::PyValueError;
 use pyo3::{PyResult, Python, pyfunction};
@@ -10,11 +9,8 @@ use serde::Deserialize;
 use serde_json::json;
 
 use crate::VERSIONS;
-use crate::client::AuthError;
-use crate::constants::{
-    ACCESS_TOKEN_ENV, ACCESS_TOKEN_PATH, AUTH_DOMAIN, CONFIG_DIR, LOGIN_CLIENT_ID,
-    REFRESH_TOKEN_PATH,
-};
+use crate::client::{AuthError, Profile};
+use crate::constants::{ACCESS_TOKEN_ENV, LOGIN_AUDIENCE, LOGIN_CLIENT_ID};
 
 #[derive(Deserialize)]
 pub(crate) struct Tokens {
@@ -35,21 +31,15 @@ pub(crate) fn get_auth_header_from_access_token_env() -> Result<Option<String>,
     Ok(None)
 }
 
-pub(crate) fn write_tokens(access_token: &str, refresh_token: &str) -> Result<(), AuthError> {
-    fs::create_dir_all(CONFIG_DIR.as_path())?;
-    fs::write(CONFIG_DIR.join(ACCESS_TOKEN_PATH), access_token)?;
-    fs::write(CONFIG_DIR.join(REFRESH_TOKEN_PATH), refresh_token)?;
-    Ok(())
-}
-
 pub(crate) async fn get_access_token_for_service_account(
+    profile: &Profile,
     username: &str,
     password: &str,
     connection_pool: reqwest_middleware::ClientWithMiddleware,
 ) -> Result<String, AuthError> {
     let url = format!(
         "https://{}/realms/Polars/protocol/openid-connect/token",
-        *AUTH_DOMAIN
+        profile.auth_domain()
     );
 
     let data = json!({
@@ -73,6 +63,7 @@ pub(crate) async fn get_access_token_for_service_account(
 }
 
 pub(crate) async fn use_refresh_token(
+    profile: &Profile,
     refresh_token: &str,
     connection_pool: reqwest_middleware::ClientWithMiddleware,
 ) -> Result<Tokens, AuthError> {
@@ -82,7 +73,7 @@ pub(crate) async fn use_refresh_token(
 
     let url = format!(
         "https://{}/realms/Polars/protocol/openid-connect/token",
-        *AUTH_DOMAIN
+        profile.auth_domain()
     );
 
     let data = json!({
@@ -101,11 +92,88 @@ pub(crate) async fn use_refresh_token(
         .await
         .map_err(|e| AuthError::new(&format!("Error parsing refresh token JSON with: {e:?}")))?;
 
-    write_tokens(&tokens.access_token, &tokens.refresh_token)?;
-
     Ok(tokens)
 }
 
+/// Exchange an external OIDC token for a Polars Cloud access token (RFC 8693).
+pub(crate) async fn exchange_federated_token(
+    profile: &Profile,
+    subject_token: &str,
+    connection_pool: reqwest_middleware::ClientWithMiddleware,
+) -> Result<String, AuthError> {
+    let url = format!(
+        "https://{}/realms/Polars/protocol/openid-connect/token",
+        profile.auth_domain()
+    );
+
+    let mut data = json!({
+        "client_id": LOGIN_CLIENT_ID,
+        "grant_type": "urn:ietf:params:oauth:grant-type:token-exchange",
+        "subject_token": subject_token,
+        "subject_token_type": "urn:ietf:params:oauth:token-type:jwt",
+        "requested_token_type": "urn:ietf:params:oauth:token-type:access_token",
+        "audience": LOGIN_AUDIENCE,
+    });
+    if let Some(issuer) = profile.federated_token_issuer() {
+        data["subject_issuer"] = issuer.into();
+    }
+
+    let token = connection_pool
+        .post(url)
+        .form(&data)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status().map_err(Into::into))
+        .map_err(|e| AuthError::new(&format!("Error exchanging federated token: {e:?}")))?
+        .json::<AccessToken>()
+        .await
+        .map_err(|e| AuthError::new(&format!("Error parsing access token JSON: {e:?}")))?;
+
+    Ok(token.access_token)
+}
+
+/// End the session of a refresh token at the auth server and revoke the token.
+///
+/// The session is ended first, as the auth server rejects a revoked token. Failing
+/// to end the session is logged, the result only reflects the revocation.
+pub(crate) async fn revoke_refresh_token(
+    profile: &Profile,
+    refresh_token: &str,
+    connection_pool: reqwest_middleware::ClientWithMiddleware,
+) -> Result<(), AuthError> {
+    let base_url = format!(
+        "https://{}/realms/Polars/protocol/openid-connect",
+        profile.auth_domain()
+    );
+
+    if let Err(e) = connection_pool
+        .post(format!("{base_url}/logout"))
+        .form(&json!({
+            "client_id": LOGIN_CLIENT_ID,
+            "refresh_token": refresh_token,
+        }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status().map_err(Into::into))
+    {
+        tracing::debug!("Error ending session: {e:?}");
+    }
+
+    connection_pool
+        .post(format!("{base_url}/revoke"))
+        .form(&json!({
+            "client_id": LOGIN_CLIENT_ID,
+            "token": refresh_token,
+            "token_type_hint": "refresh_token",
+        }))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status().map_err(Into::into))
+        .map_err(|e| AuthError::new(&format!("Error revoking token: {e:?}")))?;
+
+    Ok(())
+}
+
 #[pyfunction]
 pub(crate) fn py_is_token_expired(
     token: &str,
@@ -126,7 +194,7 @@ pub(crate) fn is_token_expired_user_friendly_error(
     })
 }
 
-/// Checks whether the JWT token has expired
+/// Checks whether the JWT token has expired or expires within `reject_tokens_expiring_in_less_than`
 pub(crate) fn is_token_expired(
     token: &str,
     reject_tokens_expiring_in_less_than: Option<Duration>,
@@ -138,7 +206,7 @@ pub(crate) fn is_token_expired(
         .ok_or_else(|| AuthError::new("No payload in token."))?
         .to_string();
 
-    let payload = STANDARD_NO_PAD
+    let payload = URL_SAFE_NO_PAD
         .decode(payload)
         .map_err(|_| AuthError::new("Invalid token payload."))?;
 
@@ -162,7 +230,7 @@ pub(crate) fn is_token_expired(
             0
         };
 
-    Ok(claims.exp < now - secs)
+    Ok(claims.exp < now + secs)
 }
 
 pub(crate) fn token_from_environment(token: String) -> Result<String, AuthError> {
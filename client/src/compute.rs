@@ -7,14 +7,104 @@ use polars_axum_models::{
     RegisterComputeClusterArgs, StartComputeClusterArgs, StartComputeClusterManifestArgs,
 };
 use polars_backend_client::client::ApiClient;
-use pyo3::exceptions::PyValueError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::{Python, pymethods};
 use uuid::Uuid;
 
 use crate::VERSIONS;
-use crate::client::WrappedAPIClient;
+use crate::client::{ClientKeyStore, ClientKeypair, KeypairOwner, WrappedAPIClient};
 use crate::error::ApiError;
 
+/// Generate the client keypair of a cluster in direct mode, `None` in proxy mode.
+fn client_keypair(mode: DBClusterModeSchema) -> Result<Option<ClientKeypair>, ApiError> {
+    if mode != DBClusterModeSchema::Direct {
+        return Ok(None);
+    }
+    ClientKeypair::generate().map(Some).map_err(|e| {
+        ApiError::PyErr(PyRuntimeError::new_err(format!(
+            "Failed to generate the client keypair: {e}"
+        )))
+    })
+}
+
+fn cluster_mode(keypair: Option<&ClientKeypair>) -> ClusterModeSchema {
+    match keypair {
+        Some(keypair) => ClusterModeSchema::Direct {
+            client_public_key: keypair.public_key_pem.clone(),
+        },
+        None => ClusterModeSchema::Proxy,
+    }
+}
+
+impl WrappedAPIClient {
+    fn client_keys(&self) -> ClientKeyStore {
+        ClientKeyStore::new(self.tokens.profile())
+    }
+
+    fn save_client_keypair(
+        &self,
+        owner: &KeypairOwner<'_>,
+        keypair: &ClientKeypair,
+    ) -> Result<(), ApiError> {
+        self.client_keys().save(owner, keypair).map_err(|e| {
+            ApiError::PyErr(PyRuntimeError::new_err(format!(
+                "Failed to store the client keypair: {e}"
+            )))
+        })
+    }
+
+    /// Register `keypair` with `register` and store it under the owner of the result.
+    ///
+    /// The keypair is stored under a pending name before it is registered, so a
+    /// cluster never runs with a key that only the control plane knows about.
+    fn register_client_keypair<'o, T>(
+        &mut self,
+        keypair: Option<&ClientKeypair>,
+        register: impl FnOnce(&mut Self) -> Result<T, ApiError>,
+        owner: impl FnOnce(&T) -> KeypairOwner<'o>,
+    ) -> Result<T, ApiError> {
+        let Some(keypair) = keypair else {
+            return register(self);
+        };
+        let store = self.client_keys();
+        let pending = KeypairOwner::Pending(keypair);
+        self.save_client_keypair(&pending, keypair)?;
+
+        let registered = match register(self) {
+            Ok(registered) => registered,
+            Err(e) => {
+                if let Err(e) = store.remove(&pending) {
+                    tracing::debug!("Failed to remove the unregistered client keypair: {e:?}");
+                }
+                return Err(e);
+            },
+        };
+        store.rename(&pending, &owner(&registered)).map_err(|e| {
+            let (_, private_key_path) = store.paths(&pending);
+            ApiError::PyErr(PyRuntimeError::new_err(format!(
+                "Failed to store the client keypair, its private key was left at {}: {e}",
+                private_key_path.display()
+            )))
+        })?;
+        Ok(registered)
+    }
+
+    /// The client keypair registered for a direct-mode cluster, if it was
+    /// started from this machine.
+    pub(crate) fn load_client_keypair(
+        &self,
+        compute_id: Uuid,
+    ) -> Result<Option<ClientKeypair>, ApiError> {
+        self.client_keys()
+            .load(&KeypairOwner::Cluster(compute_id))
+            .map_err(|e| {
+                ApiError::PyErr(PyRuntimeError::new_err(format!(
+                    "Failed to read the client keypair of cluster {compute_id}: {e}"
+                )))
+            })
+    }
+}
+
 #[pymethods]
 impl WrappedAPIClient {
     pub fn get_compute_cluster_manifest(
@@ -114,13 +204,8 @@ impl WrappedAPIClient {
         log_level: LogLevelSchema,
         idle_timeout_mins: Option<u32>,
     ) -> Result<ManifestSchema, ApiError> {
-        let mode = if mode == DBClusterModeSchema::Direct {
-            ClusterModeSchema::Direct {
-                client_public_key: "".to_string(),
-            }
-        } else {
-            ClusterModeSchema::Proxy
-        };
+        let keypair = client_keypair(mode)?;
+        let mode = cluster_mode(keypair.as_ref());
 
         if (big_instance_type.is_some() || big_instance_multiplier.is_some()) && cluster_size <= 1 {
             Err(PyValueError::new_err(
@@ -153,7 +238,7 @@ impl WrappedAPIClient {
         });
         let polars_version = VERSIONS.get().unwrap().as_ref().unwrap().0.polars;
         let params = RegisterComputeClusterArgs {
-            name,
+            name: name.clone(),
             instance,
             storage,
             big_instance_storage,
@@ -167,9 +252,18 @@ impl WrappedAPIClient {
             idle_timeout_mins,
         };
 
-        self.call(py, |client: &ApiClient| {
-            client.register_compute_cluster_manifest(workspace_id, params)
-        })
+        self.register_client_keypair(
+            keypair.as_ref(),
+            |this| {
+                this.call(py, |client: &ApiClient| {
+                    client.register_compute_cluster_manifest(workspace_id, params)
+                })
+            },
+            |_| KeypairOwner::Manifest {
+                workspace_id,
+                name: &name,
+            },
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -190,14 +284,31 @@ impl WrappedAPIClient {
         });
         let polars_version = VERSIONS.get().unwrap().as_ref().unwrap().0.polars;
         let params = StartComputeClusterManifestArgs {
-            name,
+            name: name.clone(),
             python_version,
             polars_version,
         };
 
-        self.call(py, |client: &ApiClient| {
+        let compute = self.call(py, |client: &ApiClient| {
             client.start_compute_cluster_manifest(workspace_id, params)
-        })
+        })?;
+        if compute.mode == DBClusterModeSchema::Direct {
+            let owner = KeypairOwner::Manifest {
+                workspace_id,
+                name: &name,
+            };
+            let keypair = self.client_keys().load(&owner).map_err(|e| {
+                ApiError::PyErr(PyRuntimeError::new_err(format!(
+                    "Failed to read the client keypair of manifest {name}: {e}"
+                )))
+            })?;
+            // Clusters of a manifest share its keypair, so direct connections look it up
+            // by cluster id like for clusters started without a manifest.
+            if let Some(keypair) = keypair {
+                self.save_client_keypair(&KeypairOwner::Cluster(compute.id), &keypair)?;
+            }
+        }
+        Ok(compute)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -220,13 +331,8 @@ impl WrappedAPIClient {
         log_level: Option<LogLevelSchema>,
         idle_timeout_mins: Option<u32>,
     ) -> Result<ComputeSchema, ApiError> {
-        let mode = if mode == DBClusterModeSchema::Direct {
-            ClusterModeSchema::Direct {
-                client_public_key: "".to_string(),
-            }
-        } else {
-            ClusterModeSchema::Proxy
-        };
+        let keypair = client_keypair(mode)?;
+        let mode = cluster_mode(keypair.as_ref());
 
         if (big_instance_type.is_some() || big_instance_multiplier.is_some()) && cluster_size <= 1 {
             Err(PyValueError::new_err(
@@ -272,9 +378,15 @@ impl WrappedAPIClient {
             idle_timeout_mins,
         };
 
-        self.call(py, |client: &ApiClient| {
-            client.start_compute_cluster(workspace_id, params)
-        })
+        self.register_client_keypair(
+            keypair.as_ref(),
+            |this| {
+                this.call(py, |client: &ApiClient| {
+                    client.start_compute_cluster(workspace_id, params)
+                })
+            },
+            |compute| KeypairOwner::Cluster(compute.id),
+        )
     }
 
     #[pyo3(signature=(workspace_id, *, status=None))]
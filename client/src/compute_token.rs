@@ -0,0 +1,110 @@
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use polars_axum_models::ComputeClusterPublicInfoSchema;
+use polars_backend_client::client::ApiClient;
+use polars_backend_client::error::ApiError as ClientApiError;
+use uuid::Uuid;
+
+use crate::client::{TokenManager, WrappedAPIClient, is_token_expired};
+use crate::error::{ApiError, Result};
+
+/// Compute tokens that expire within this margin are refreshed before use.
+const COMPUTE_TOKEN_REFRESH_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Compute token of a direct-mode cluster, fetched from the control plane and
+/// refreshed before it expires.
+///
+/// Clones share the cached token.
+#[derive(Clone)]
+pub(crate) struct ComputeTokenSource {
+    inner: Arc<ComputeTokenSourceInner>,
+}
+
+struct ComputeTokenSourceInner {
+    rest: ApiClient,
+    tokens: TokenManager,
+    workspace_id: Uuid,
+    compute_id: Uuid,
+    token: RwLock<Option<String>>,
+    /// Held while refreshing, so concurrent calls fetch a single new token.
+    refresh: tokio::sync::Mutex<()>,
+}
+
+impl ComputeTokenSource {
+    pub(crate) fn new(client: &WrappedAPIClient, workspace_id: Uuid, compute_id: Uuid) -> Self {
+        ComputeTokenSource {
+            inner: Arc::new(ComputeTokenSourceInner {
+                rest: client.rest.clone(),
+                tokens: client.tokens.clone(),
+                workspace_id,
+                compute_id,
+                token: RwLock::new(None),
+                refresh: tokio::sync::Mutex::new(()),
+            }),
+        }
+    }
+
+    /// The cached token, without checking whether it expired.
+    pub(crate) fn current(&self) -> Option<String> {
+        self.inner.token.read().unwrap().clone()
+    }
+
+    fn is_fresh(token: &str) -> bool {
+        matches!(
+            is_token_expired(token, Some(COMPUTE_TOKEN_REFRESH_SKEW)),
+            Ok(false)
+        )
+    }
+
+    /// Make sure the cached token does not expire soon, fetching a new one if it does.
+    pub(crate) async fn refresh_if_expiring(&self) -> Result<()> {
+        if self.current().as_deref().is_some_and(Self::is_fresh) {
+            return Ok(());
+        }
+        let _guard = self.inner.refresh.lock().await;
+        // Another call may have refreshed the token while we waited for the lock.
+        if self.current().as_deref().is_some_and(Self::is_fresh) {
+            return Ok(());
+        }
+
+        tracing::debug!(
+            "Fetching compute token for cluster {}",
+            self.inner.compute_id
+        );
+        let response = match self
+            .inner
+            .rest
+            .get_compute_cluster_token(self.inner.workspace_id, self.inner.compute_id)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return Err(self.api_error(e).await),
+        };
+        *self.inner.token.write().unwrap() = Some(response.token);
+        Ok(())
+    }
+
+    /// Fetch the address and server certificate of the cluster, to reconnect
+    /// after the cluster rotated its certificate.
+    pub(crate) async fn fetch_public_info(&self) -> Result<ComputeClusterPublicInfoSchema> {
+        match self
+            .inner
+            .rest
+            .get_public_server_info(self.inner.workspace_id, self.inner.compute_id)
+            .await
+        {
+            Ok(info) => Ok(info),
+            Err(e) => Err(self.api_error(e).await),
+        }
+    }
+
+    async fn api_error(&self, e: ClientApiError) -> ApiError {
+        ApiError::from_with_auth_method(e, self.inner.tokens.method().await)
+    }
+
+    /// Drop the cached token after the scheduler rejected it.
+    pub(crate) fn invalidate(&self) {
+        *self.inner.token.write().unwrap() = None;
+    }
+}
@@ -1,5 +1,4 @@
 use std::path::PathBuf;
-use std::string::ToString;
 use std::sync::LazyLock;
 
 use anyhow::anyhow;
@@ -13,22 +12,29 @@ pub(crate) static LOGIN_CLIENT_ID: &str = "PolarsCloud";
 pub(crate) static LOGIN_AUDIENCE: &str = "account";
 
 pub(crate) static ACCESS_TOKEN_ENV: &str = "POLARS_CLOUD_ACCESS_TOKEN";
+pub(crate) static TOKENS_PATH: &str = "cloud_tokens.json";
 pub(crate) static ACCESS_TOKEN_PATH: &str = "cloud_access_token";
 pub(crate) static REFRESH_TOKEN_PATH: &str = "cloud_refresh_token";
 
-pub(crate) static AUTH_DOMAIN: LazyLock<String> = LazyLock::new(|| {
-    let domain =
-        std::env::var("POLARS_CLOUD_DOMAIN").unwrap_or_else(|_e| "prd.cloud.pola.rs".to_string());
-    format!("auth.{domain}")
-});
+pub(crate) static CLIENT_ID_ENV: &str = "POLARS_CLOUD_CLIENT_ID";
+pub(crate) static CLIENT_SECRET_ENV: &str = "POLARS_CLOUD_CLIENT_SECRET";
+pub(crate) static CLIENT_SECRET_FILE_ENV: &str = "POLARS_CLOUD_CLIENT_SECRET_FILE";
+pub(crate) static CREDENTIAL_PROCESS_ENV: &str = "POLARS_CLOUD_CREDENTIAL_PROCESS";
 
-pub(crate) static API_ADDR: LazyLock<String> = LazyLock::new(|| {
-    let domain =
-        std::env::var("POLARS_CLOUD_DOMAIN").unwrap_or_else(|_e| "prd.cloud.pola.rs".to_string());
-    let prefix =
-        std::env::var("POLARS_CLOUD_API_DOMAIN_PREFIX").unwrap_or_else(|_e| "api".to_string());
-    format!("https://{prefix}.{domain}")
-});
+pub(crate) static FEDERATED_TOKEN_ENV: &str = "POLARS_CLOUD_FEDERATED_TOKEN";
+pub(crate) static FEDERATED_TOKEN_FILE_ENV: &str = "POLARS_CLOUD_FEDERATED_TOKEN_FILE";
+pub(crate) static FEDERATED_TOKEN_ISSUER_ENV: &str = "POLARS_CLOUD_FEDERATED_TOKEN_ISSUER";
+
+pub(crate) static GRPC_COMPRESSION_ENV: &str = "POLARS_CLOUD_GRPC_COMPRESSION";
+pub(crate) static GRPC_COMPRESSION_THRESHOLD_ENV: &str = "POLARS_CLOUD_GRPC_COMPRESSION_THRESHOLD";
+
+pub(crate) static DEFAULT_DOMAIN: &str = "prd.cloud.pola.rs";
+
+pub(crate) static PROFILE_ENV: &str = "POLARS_CLOUD_PROFILE";
+pub(crate) static DEFAULT_PROFILE: &str = "default";
+pub(crate) static CONFIG_FILE_ENV: &str = "POLARS_CLOUD_CONFIG_FILE";
+pub(crate) static CONFIG_FILE_PATH: &str = "config";
+pub(crate) static PROFILES_DIR: &str = "profiles";
 
 pub(crate) static CONFIG_DIR: LazyLock<PathBuf> = LazyLock::new(|| {
     std::env::var("POLARS_CLOUD_CONFIG_DIR")
@@ -37,6 +37,8 @@ pub enum ApiError {
     GRPCError(#[from] Status),
     #[error("{0}")]
     UuidParsingError(#[from] uuid::Error),
+    #[error("{0}")]
+    ArrowError(#[from] arrow_schema::ArrowError),
 }
 
 impl ApiError {
@@ -85,6 +87,13 @@ impl From<polars_backend_client::error::ApiError> for ApiError {
     fn from(value: polars_backend_client::error::ApiError) -> Self {
         match value {
             polars_backend_client::error::ApiError::ReqwestError(e) => ApiError::ReqwestError(e),
+            // Refreshing the token inside the auth middleware failed
+            polars_backend_client::error::ApiError::MiddlewareError(
+                reqwest_middleware::Error::Middleware(e),
+            ) if e.is::<AuthError>() => match e.downcast::<AuthError>() {
+                Ok(e) => e.into(),
+                Err(e) => ApiError::MiddlewareError(reqwest_middleware::Error::Middleware(e)),
+            },
             polars_backend_client::error::ApiError::MiddlewareError(e) => {
                 ApiError::MiddlewareError(e)
             },
@@ -178,6 +187,9 @@ impl From<ApiError> for PyErr {
                 PyRuntimeError::new_err(message)
             },
             ApiError::UuidParsingError(error) => PyValueError::new_err(error.to_string()),
+            ApiError::ArrowError(error) => {
+                PyRuntimeError::new_err(format!("Error converting to Arrow, {error}"))
+            },
         }
     }
 }
@@ -1,12 +1,15 @@
 mod aws;
 pub mod client;
 mod compute;
+mod compute_token;
 mod constants;
 mod error;
 mod organization;
 mod query;
 mod query_control_grpc;
 pub mod query_grpc;
+mod query_handle;
+mod query_history;
 mod query_settings;
 mod runtime;
 mod serde_types;
@@ -20,7 +23,7 @@ use client::{polars_version, py_is_token_expired, python_version};
 use polars_axum_models::{
     ComputeClusterPublicInfoSchema, ComputeSchema, ComputeStatusSchema, ComputeTokenSchema,
     DBClusterModeSchema, DeleteWorkspaceSchema, FileTypeSchema, LogLevelSchema, OrganizationSchema,
-    QueryPlansSchema, QuerySchema, QueryStateTimingSchema, QueryStatusCodeSchema,
+    QueryCountSchema, QueryPlansSchema, QuerySchema, QueryStateTimingSchema, QueryStatusCodeSchema,
     QueryWithStateTimingAndResultSchema, QueryWithStateTimingSchema, QueryWithStatusSchema,
     ResultSchema, StatusSchema, TerminationReasonSchema, TerminationSchema, VersionNumber,
     WorkspaceSchema, WorkspaceSetupUrlSchema, WorkspaceStateSchema, WorkspaceWithUrlSchema,
@@ -32,10 +35,15 @@ use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyString};
 
 use self::query_settings::PyShuffleOpts;
-use crate::client::WrappedAPIClient;
+use crate::client::{
+    CredentialSource, DeviceLoginProgress, GrpcCompression, LogoutResult, Profile, TokenClaims,
+    WrappedAPIClient,
+};
 use crate::error::{AuthLoadError, EncodedPolarsError, NotFoundError};
 use crate::query_grpc::*;
+use crate::query_handle::QueryHandle;
 use crate::query_settings::PyQuerySettings;
+use crate::user::Identity;
 use crate::workspace::*;
 
 #[derive(Clone, Copy)]
@@ -101,7 +109,14 @@ fn polars_cloud(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<PyShuffleOpts>().unwrap();
     m.add_class::<PyQuerySettings>().unwrap();
     m.add_class::<WrappedAPIClient>().unwrap();
+    m.add_class::<Profile>().unwrap();
+    m.add_class::<CredentialSource>().unwrap();
+    m.add_class::<TokenClaims>().unwrap();
+    m.add_class::<DeviceLoginProgress>().unwrap();
+    m.add_class::<LogoutResult>().unwrap();
+    m.add_class::<Identity>().unwrap();
     m.add_class::<SchedulerClient>().unwrap();
+    m.add_class::<QueryHandle>().unwrap();
 
     m.add_class::<WorkspaceSchema>().unwrap();
     m.add_class::<WorkspaceStateSchema>().unwrap();
@@ -109,6 +124,7 @@ fn polars_cloud(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
 
     m.add_class::<QuerySchema>().unwrap();
     m.add_class::<QueryPlansSchema>().unwrap();
+    m.add_class::<QueryCountSchema>().unwrap();
     m.add_class::<QueryStatusCodeSchema>().unwrap();
     m.add_class::<StatusSchema>().unwrap();
     m.add_class::<QueryWithStatusSchema>().unwrap();
@@ -135,8 +151,13 @@ fn polars_cloud(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<OrganizationSchema>().unwrap();
 
     m.add_class::<ClientOptions>().unwrap();
+    m.add_class::<GrpcCompression>().unwrap();
 
     m.add_class::<QueryPlansPy>().unwrap();
+    m.add_class::<QueryWatchEventPy>().unwrap();
+    m.add_class::<QueryWatchIterator>().unwrap();
+    m.add_class::<ResultChunkPy>().unwrap();
+    m.add_class::<ResultChunkIterator>().unwrap();
     m.add_class::<PlanFormatPy>().unwrap();
 
     m.add("NotFoundError", m.py().get_type::<NotFoundError>())
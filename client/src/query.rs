@@ -1,14 +1,43 @@
 #![allow(clippy::result_large_err)]
 
+use chrono::{DateTime, FixedOffset, Utc};
+use futures::{StreamExt, TryStreamExt};
 use polars_axum_models::{
-    Pagination, QueryParamsFilter, QueryWithStateTimingAndResultSchema, QueryWithStateTimingSchema,
+    MetricWindow, Pagination, QueryCountParams, QueryCountSchema, QueryParamsFilter,
+    QueryPlansSchema, QueryStatusCodeSchema, QueryWithStateTimingAndResultSchema,
+    QueryWithStateTimingSchema, TimeWindow,
 };
 use polars_backend_client::client::ApiClient;
-use pyo3::{Python, pymethods};
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyBytes;
+use pyo3::{Py, Python, pymethods};
 use uuid::Uuid;
 
 use crate::client::WrappedAPIClient;
 use crate::error::ApiError;
+use crate::query_history::query_history_to_ipc;
+
+/// Number of query details that are fetched concurrently for an export.
+const QUERY_DETAILS_CONCURRENCY: usize = 16;
+
+impl WrappedAPIClient {
+    fn get_filtered_queries(
+        &mut self,
+        py: Python,
+        workspace_id: Uuid,
+        filters: QueryParamsFilter,
+    ) -> Result<Vec<QueryWithStateTimingSchema>, ApiError> {
+        self.call_paginated(py, |client: &ApiClient, page: i64| {
+            // TODO: offset is overridden later by (page - 1) * limit, confusing
+            let pagination = Pagination {
+                page,
+                limit: 1000,
+                offset: 0,
+            };
+            client.get_queries(workspace_id, filters.clone(), pagination)
+        })
+    }
+}
 
 #[pymethods]
 impl WrappedAPIClient {
@@ -24,6 +53,17 @@ impl WrappedAPIClient {
         })
     }
 
+    pub fn get_query_plans(
+        &mut self,
+        py: Python,
+        workspace_id: Uuid,
+        query_id: Uuid,
+    ) -> Result<QueryPlansSchema, ApiError> {
+        self.call(py, |client: &ApiClient| {
+            client.get_query_plans(workspace_id, query_id)
+        })
+    }
+
     #[pyo3(signature=(workspace_id, query_id))]
     pub fn cancel_proxy_query(
         &mut self,
@@ -36,21 +76,142 @@ impl WrappedAPIClient {
         })
     }
 
-    #[pyo3(signature=(workspace_id))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature=(
+        workspace_id,
+        *,
+        cluster_id=None,
+        user_id=None,
+        status=None,
+        label=None,
+        requested_after=None,
+        requested_before=None,
+        ended_after=None,
+        ended_before=None,
+        min_duration_secs=None
+    ))]
     pub fn get_queries(
         &mut self,
         py: Python,
         workspace_id: Uuid,
+        cluster_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+        status: Option<Vec<QueryStatusCodeSchema>>,
+        label: Option<String>,
+        requested_after: Option<DateTime<Utc>>,
+        requested_before: Option<DateTime<Utc>>,
+        ended_after: Option<DateTime<Utc>>,
+        ended_before: Option<DateTime<Utc>>,
+        min_duration_secs: Option<i64>,
     ) -> Result<Vec<QueryWithStateTimingSchema>, ApiError> {
-        self.call_paginated(py, |client: &ApiClient, page: i64| {
-            // TODO: offset is overridden later by (page - 1) * limit, confusing
-            let pagination = Pagination {
-                page,
-                limit: 1000,
-                offset: 0,
-            };
-            let params = QueryParamsFilter::default();
-            client.get_queries(workspace_id, params, pagination)
+        let filters = QueryParamsFilter {
+            cluster_id,
+            user_id,
+            status,
+            label,
+            requested_after,
+            requested_before,
+            ended_after,
+            ended_before,
+            min_duration_secs,
+        };
+        self.get_filtered_queries(py, workspace_id, filters)
+    }
+
+    /// Export the query history as an Arrow IPC stream.
+    ///
+    /// With `include_results` the details of every query are fetched as well
+    /// to fill in the stage and result statistics, at the cost of one request
+    /// per query.
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature=(
+        workspace_id,
+        *,
+        cluster_id=None,
+        user_id=None,
+        status=None,
+        label=None,
+        requested_after=None,
+        requested_before=None,
+        ended_after=None,
+        ended_before=None,
+        min_duration_secs=None,
+        include_results=false
+    ))]
+    pub fn get_queries_ipc(
+        &mut self,
+        py: Python,
+        workspace_id: Uuid,
+        cluster_id: Option<Uuid>,
+        user_id: Option<Uuid>,
+        status: Option<Vec<QueryStatusCodeSchema>>,
+        label: Option<String>,
+        requested_after: Option<DateTime<Utc>>,
+        requested_before: Option<DateTime<Utc>>,
+        ended_after: Option<DateTime<Utc>>,
+        ended_before: Option<DateTime<Utc>>,
+        min_duration_secs: Option<i64>,
+        include_results: bool,
+    ) -> Result<Py<PyBytes>, ApiError> {
+        let filters = QueryParamsFilter {
+            cluster_id,
+            user_id,
+            status,
+            label,
+            requested_after,
+            requested_before,
+            ended_after,
+            ended_before,
+            min_duration_secs,
+        };
+        let queries = self.get_filtered_queries(py, workspace_id, filters)?;
+
+        let rows = if include_results {
+            self.call(py, |client: &ApiClient| {
+                futures::stream::iter(queries)
+                    .map(|q| client.get_query(workspace_id, q.query.id))
+                    .buffered(QUERY_DETAILS_CONCURRENCY)
+                    .try_collect::<Vec<_>>()
+            })?
+        } else {
+            queries
+                .into_iter()
+                .map(|q| QueryWithStateTimingAndResultSchema {
+                    query: q.query,
+                    state_timing: q.state_timing,
+                    result: None,
+                })
+                .collect()
+        };
+
+        let buf = py.detach(|| query_history_to_ipc(&rows))?;
+        Ok(PyBytes::new(py, &buf).unbind())
+    }
+
+    /// Number of queries per `interval` bucket between `start` and `end`.
+    #[pyo3(signature=(workspace_id, start, end, interval, *, cluster_id=None))]
+    pub fn get_query_count(
+        &mut self,
+        py: Python,
+        workspace_id: Uuid,
+        start: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+        interval: chrono::Duration,
+        cluster_id: Option<Uuid>,
+    ) -> Result<Vec<QueryCountSchema>, ApiError> {
+        if interval <= chrono::Duration::zero() {
+            return Err(PyValueError::new_err("`interval` must be positive").into());
+        }
+        if end < start {
+            return Err(PyValueError::new_err("`end` must not be before `start`").into());
+        }
+        let params = QueryCountParams { cluster_id };
+        let window = MetricWindow {
+            window: TimeWindow { start, end },
+            interval,
+        };
+        self.call(py, |client: &ApiClient| async move {
+            client.get_query_count(workspace_id, &params, &window).await
         })
     }
 }
@@ -1,12 +1,12 @@
 #![allow(clippy::result_large_err)]
 
 use protos_client_control::{
-    ClientServiceClient, MAX_MESSAGE_LENGTH_CONTROL_PLANE, SubmitQueryRequestProto, client,
+    ClientServiceClient, MAX_MESSAGE_LENGTH_CONTROL_PLANE, PLAN_CHUNK_LENGTH,
+    SubmitQueryRequestProto, client,
 };
 use protos_common::prost::Message;
 use protos_common::tonic::Request;
 use protos_common::{QueryIdentifier, QueryInfo};
-use pyo3::exceptions::PyValueError;
 use pyo3::{Python, pymethods};
 use uuid::Uuid;
 
@@ -23,7 +23,6 @@ impl WrappedAPIClient {
         query_id: Uuid,
     ) -> Result<QueryInfoPy, ApiError> {
         let query_id = QueryIdentifier::from(query_id);
-        let req = Request::new(query_id.into());
         let resp = self.call_grpc(
             py,
             |mut client: ControlPlaneGRPCClient, request: Request<_>| async move {
@@ -32,7 +31,7 @@ impl WrappedAPIClient {
                     .await
                     .map(|res| res.into_inner())
             },
-            req,
+            || query_id.into(),
         );
         resp.map(|query_info| query_result_to_py(py, query_info.into(), None))
     }
@@ -45,31 +44,63 @@ impl WrappedAPIClient {
         settings: PyQuerySettings,
         labels: Option<Vec<String>>,
     ) -> Result<Uuid, ApiError> {
-        let proto: SubmitQueryRequestProto = client::SubmitQueryRequest {
+        let request = client::SubmitQueryRequest {
             compute_id: compute_id.into(),
             settings: settings.into(),
             plan: plan.into(),
             query_info: QueryInfo {
                 labels: labels.unwrap_or_default(),
             },
+        };
+        let proto: SubmitQueryRequestProto = request.clone().into();
+        let encoded_len = proto.encoded_len();
+        // The first attempt sends the message that was already built for the size check
+        let mut proto = Some(proto);
+        loop {
+            let encoding = self.grpc_compression.encoding_for(encoded_len);
+            let result = if encoded_len > MAX_MESSAGE_LENGTH_CONTROL_PLANE {
+                // The plan does not fit in a single message, upload it in chunks.
+                self.call_grpc(
+                    py,
+                    |client: ClientServiceClient<_>, request: Request<_>| async move {
+                        let mut client = match encoding {
+                            Some(encoding) => client.send_compressed(encoding),
+                            None => client,
+                        };
+                        client
+                            .submit_query_chunked(request)
+                            .await
+                            .map(|res| QueryIdentifier::from(res.into_inner()).inner)
+                    },
+                    // The chunks share the buffer of the plan, so rebuilding them is cheap
+                    || futures::stream::iter(request.clone().into_chunks(PLAN_CHUNK_LENGTH)),
+                )
+            } else {
+                self.call_grpc(
+                    py,
+                    |client: ClientServiceClient<_>, request: Request<_>| async move {
+                        let mut client = match encoding {
+                            Some(encoding) => client.send_compressed(encoding),
+                            None => client,
+                        };
+                        client
+                            .submit_query(request)
+                            .await
+                            .map(|res| QueryIdentifier::from(res.into_inner()).inner)
+                    },
+                    || proto.take().unwrap_or_else(|| request.clone().into()),
+                )
+            };
+            // Send the plan again if the control plane does not accept the compression.
+            match result {
+                Err(ApiError::GRPCError(status)) if self.grpc_compression.renegotiate(&status) => {
+                    tracing::debug!(
+                        "Control plane rejected the gRPC compression: {}",
+                        status.message()
+                    );
+                },
+                result => return result,
+            }
         }
-        .into();
-        if proto.encoded_len() > MAX_MESSAGE_LENGTH_CONTROL_PLANE {
-            return Err(ApiError::PyErr(PyValueError::new_err(format!(
-                "Query plan exceeds limit of {} MiB",
-                MAX_MESSAGE_LENGTH_CONTROL_PLANE / 1024 / 1024
-            ))));
-        }
-        let req = Request::new(proto);
-        self.call_grpc(
-            py,
-            |mut client: ClientServiceClient<_>, request: Request<_>| async move {
-                client
-                    .submit_query(request)
-                    .await
-                    .map(|res| QueryIdentifier::from(res.into_inner()).inner)
-            },
-            req,
-        )
     }
 }
@@ -1,46 +1,85 @@
 #![allow(clippy::result_large_err)]
 
+use std::sync::Mutex;
 use std::time::Duration;
 
+use futures::StreamExt;
+use futures::stream::BoxStream;
 use polars_axum_models::QueryStatusCodeSchema;
 use polars_backend_client::client::user_agent;
 use protos_client_compute::client::client::SubmitQueryRequest;
 use protos_client_compute::client::{
-    ClientServiceClient, GetQueryPlansRequest, GetQueryResultResponse, PlanSelection, QueryStatus,
+    ClientServiceClient, FetchResultRequest, GetQueryPlansRequest, GetQueryResultResponse,
+    PlanSelection, QueryStatus, QueryWatchEvent, ResultChunk, SubmitQueryRequestProto,
 };
 use protos_client_compute::observatory::{
     GetQueryProfileRequest, QueryProfile, QueryProfileServiceClient,
 };
+use protos_common::prost::Message;
 use protos_common::tonic::codegen::http::uri::Scheme;
+use protos_common::tonic::service::Interceptor;
 use protos_common::tonic::service::interceptor::InterceptedService;
 use protos_common::tonic::transport::{Certificate, Channel, ClientTlsConfig, Identity, Uri};
 use protos_common::tonic::{self, Code, Request};
 use protos_common::{
-    MAX_MESSAGE_LENGTH_UNLIMITED, PlanFormat, QueryIdentifier, QueryInfo, QueryPlans,
+    ACCEPTED_COMPRESSION_ENCODINGS, Compression, MAX_MESSAGE_LENGTH_UNLIMITED, PlanFormat,
+    QueryIdentifier, QueryInfo, QueryPlans,
 };
 use pyo3::exceptions::PyRuntimeError;
-use pyo3::{PyErr, Python, pyclass, pymethods};
+use pyo3::types::PyBytes;
+use pyo3::{Py, PyErr, PyRef, PyRefMut, Python, pyclass, pymethods};
 use reqwest::header::AUTHORIZATION;
-use utils::{Backoff, Fixed, retry};
+use utils::{Backoff, Exponential, Fixed, retry};
 use uuid::Uuid;
 
 use crate::VERSIONS;
+use crate::client::{GrpcCompression, WrappedAPIClient};
+use crate::compute_token::ComputeTokenSource;
 use crate::constants::RUNTIME;
 use crate::error::{ApiError, Result};
 use crate::query_settings::PyQuerySettings;
 use crate::serde_types::{QueryInfoPy, QueryProfilePy, query_profile_to_py, query_result_to_py};
 
-type SchedulerGRPCClient =
-    ClientServiceClient<InterceptedService<Channel, fn(Request<()>) -> tonic::Result<Request<()>>>>;
+type SchedulerGRPCClient = ClientServiceClient<InterceptedService<Channel, SchedulerInterceptor>>;
 
-type ObservatoryClient = QueryProfileServiceClient<
-    InterceptedService<Channel, fn(Request<()>) -> tonic::Result<Request<()>>>,
->;
+type ObservatoryClient =
+    QueryProfileServiceClient<InterceptedService<Channel, SchedulerInterceptor>>;
+
+/// Port the scheduler of a cluster listens on for direct connections.
+const DEFAULT_SCHEDULER_PORT: u16 = 5051;
+
+/// Adds the client versions and, for clusters of which the client manages the
+/// compute token, the cached token to every request.
+///
+/// A token set on the request by the caller takes precedence.
+#[derive(Clone)]
+struct SchedulerInterceptor {
+    token_source: Option<ComputeTokenSource>,
+}
+
+impl Interceptor for SchedulerInterceptor {
+    fn call(&mut self, request: Request<()>) -> tonic::Result<Request<()>> {
+        let mut request = version_interceptor(request)?;
+        if !request.metadata().contains_key(AUTHORIZATION.as_str())
+            && let Some(token) = self
+                .token_source
+                .as_ref()
+                .and_then(ComputeTokenSource::current)
+        {
+            request = insert_auth_token(request, Some(token));
+        }
+        Ok(request)
+    }
+}
 
 #[pyclass]
 pub struct SchedulerClient {
+    address: String,
+    client_options: ClientOptions,
+    token_source: Option<ComputeTokenSource>,
     scheduler_client: SchedulerGRPCClient,
     observability_client: ObservatoryClient,
+    compression: Compression,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -57,6 +96,341 @@ pub struct QueryPlansPy {
     pub phys_plan: Option<String>,
 }
 
+/// A status transition or stage progress of a watched query.
+#[pyclass(get_all)]
+pub struct QueryWatchEventPy {
+    pub status: QueryStatusCodeSchema,
+    pub total_stages: u32,
+    pub finished_stages: u32,
+    pub failed_stages: u32,
+}
+
+/// Iterator over the [`QueryWatchEventPy`]s of a query, ends once the query is done.
+#[pyclass]
+pub struct QueryWatchIterator {
+    events: Mutex<BoxStream<'static, tonic::Result<QueryWatchEvent>>>,
+}
+
+#[pymethods]
+impl QueryWatchIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Result<Option<QueryWatchEventPy>> {
+        let events = self.events.get_mut().unwrap();
+        let Some(event) = RUNTIME.block_on(py, events.next())? else {
+            return Ok(None);
+        };
+        let QueryWatchEvent { status, progress } = event?;
+        Ok(Some(QueryWatchEventPy {
+            status: query_status_to_py(status)?,
+            total_stages: progress.total_stages,
+            finished_stages: progress.finished_stages,
+            failed_stages: progress.failed_stages,
+        }))
+    }
+}
+
+/// A chunk of a query result, holding a complete Arrow IPC stream.
+#[pyclass(get_all)]
+pub struct ResultChunkPy {
+    pub index: u64,
+    pub total_chunks: u64,
+    pub num_rows: u64,
+    pub data: Py<PyBytes>,
+}
+
+/// Iterator over the chunks of a query result.
+///
+/// Chunks are only pulled from the cluster as they are consumed, so a slow
+/// consumer holds the cluster back through the flow control of the stream.
+#[pyclass]
+pub struct ResultChunkIterator {
+    chunks: Mutex<BoxStream<'static, tonic::Result<ResultChunk>>>,
+    total_chunks: Option<u64>,
+    /// Index of the next chunk, to resume the download from after an error.
+    #[pyo3(get)]
+    next_chunk: u64,
+    #[pyo3(get)]
+    received_bytes: u64,
+}
+
+#[pymethods]
+impl ResultChunkIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> Result<Option<ResultChunkPy>> {
+        let chunks = self.chunks.get_mut().unwrap();
+        let Some(chunk) = RUNTIME.block_on(py, chunks.next())? else {
+            return match self.total_chunks {
+                Some(total_chunks) if self.next_chunk < total_chunks => {
+                    Err(ApiError::PyErr(PyRuntimeError::new_err(format!(
+                        "Result stream ended after chunk {} of {total_chunks}",
+                        self.next_chunk
+                    ))))
+                },
+                _ => Ok(None),
+            };
+        };
+        let chunk = chunk?;
+        if chunk.index != self.next_chunk {
+            return Err(ApiError::PyErr(PyRuntimeError::new_err(format!(
+                "Cluster sent result chunk {} while chunk {} was expected",
+                chunk.index, self.next_chunk
+            ))));
+        }
+
+        self.total_chunks = Some(chunk.total_chunks);
+        self.next_chunk += 1;
+        self.received_bytes += chunk.data.len() as u64;
+        Ok(Some(ResultChunkPy {
+            index: chunk.index,
+            total_chunks: chunk.total_chunks,
+            num_rows: chunk.num_rows,
+            data: PyBytes::new(py, &chunk.data).unbind(),
+        }))
+    }
+}
+
+fn query_status_to_py(status: QueryStatus) -> Result<QueryStatusCodeSchema> {
+    match status {
+        QueryStatus::Unspecified => Err(ApiError::PyErr(PyRuntimeError::new_err(
+            "Server returned unknown query status code",
+        ))),
+        QueryStatus::Scheduled => Ok(QueryStatusCodeSchema::Scheduled),
+        QueryStatus::InProgress => Ok(QueryStatusCodeSchema::InProgress),
+        QueryStatus::Success => Ok(QueryStatusCodeSchema::Success),
+        QueryStatus::Failed => Ok(QueryStatusCodeSchema::Failed),
+        QueryStatus::Canceled => Ok(QueryStatusCodeSchema::Canceled),
+    }
+}
+
+/// How a failed compute-plane call is retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RetryPolicy {
+    /// Calls without side effects, retried whenever the scheduler is unavailable.
+    Idempotent,
+    /// Query submission, only retried if the request never reached the scheduler,
+    /// so a query is not submitted twice.
+    Submission,
+}
+
+impl RetryPolicy {
+    fn should_retry(self, status: &tonic::Status) -> bool {
+        match self {
+            RetryPolicy::Idempotent => status.code() == Code::Unavailable,
+            RetryPolicy::Submission => is_connect_error(status),
+        }
+    }
+}
+
+/// Whether the call failed because no connection to the scheduler could be made.
+fn is_connect_error(status: &tonic::Status) -> bool {
+    let mut source = std::error::Error::source(status);
+    while let Some(err) = source {
+        if err.is::<tonic::ConnectError>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Whether the TLS handshake failed because the certificate of the scheduler
+/// is not the pinned one.
+fn is_certificate_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        // The TLS stream reports rustls errors wrapped in an I/O error, whose
+        // source skips the wrapped error.
+        let err = err
+            .downcast_ref::<std::io::Error>()
+            .and_then(std::io::Error::get_ref)
+            .map_or(err, |inner| inner as _);
+        if let Some(rustls::Error::InvalidCertificate(_)) = err.downcast_ref::<rustls::Error>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+fn certificate_mismatch(address: &str) -> tonic::Status {
+    tonic::Status::unavailable(format!(
+        "The scheduler at {address} presented a certificate that does not match the pinned server certificate of the cluster"
+    ))
+}
+
+/// Whether the status was produced by the transport rather than sent by the scheduler.
+fn is_transport_error(status: &tonic::Status) -> bool {
+    status.code() == Code::Unavailable && std::error::Error::source(status).is_some()
+}
+
+fn default_backoff() -> impl Backoff {
+    Exponential::new(Duration::from_millis(100))
+        .maximum(Duration::from_secs(5))
+        .deadline(Duration::from_secs(60))
+}
+
+impl SchedulerClient {
+    async fn connect(
+        address: &str,
+        client_options: ClientOptions,
+        token_source: Option<ComputeTokenSource>,
+    ) -> Result<(SchedulerGRPCClient, ObservatoryClient)> {
+        let channel = get_channel(address, client_options).await?;
+        let interceptor = SchedulerInterceptor { token_source };
+        let scheduler_client = ACCEPTED_COMPRESSION_ENCODINGS.into_iter().fold(
+            ClientServiceClient::with_interceptor(channel.clone(), interceptor.clone())
+                .max_encoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED)
+                .max_decoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED),
+            |client, encoding| client.accept_compressed(encoding),
+        );
+
+        let observability_client = ACCEPTED_COMPRESSION_ENCODINGS.into_iter().fold(
+            QueryProfileServiceClient::with_interceptor(channel, interceptor)
+                .max_encoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED)
+                .max_decoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED),
+            |client, encoding| client.accept_compressed(encoding),
+        );
+        Ok((scheduler_client, observability_client))
+    }
+
+    /// Replace the channel after the transport broke.
+    async fn reconnect(&mut self) -> Result<()> {
+        tracing::debug!("Reconnecting to scheduler at {}", self.address);
+        let (scheduler_client, observability_client) = Self::connect(
+            &self.address,
+            self.client_options.clone(),
+            self.token_source.clone(),
+        )
+        .await?;
+        self.scheduler_client = scheduler_client;
+        self.observability_client = observability_client;
+        Ok(())
+    }
+
+    /// Re-fetch the server certificate of the cluster after the TLS handshake
+    /// rejected the pinned one, and reconnect if it was rotated.
+    ///
+    /// Returns whether the certificate changed.
+    async fn rotate_server_certificate(&mut self) -> Result<bool> {
+        let Some(token_source) = &self.token_source else {
+            return Ok(false);
+        };
+        let server_info = token_source.fetch_public_info().await?;
+        let server_crt = server_info.public_server_key.into_bytes();
+        if self.client_options.public_server_crt.as_ref() == Some(&server_crt) {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            "Server certificate of cluster {} was rotated, reconnecting with the new certificate",
+            server_info.cluster_id
+        );
+        self.client_options.public_server_crt = Some(server_crt);
+        self.address = format!("{}:{DEFAULT_SCHEDULER_PORT}", server_info.public_address);
+        self.reconnect().await?;
+        Ok(true)
+    }
+
+    /// Make a call with the default backoff, see [`SchedulerClient::call_with`].
+    async fn call<T, F, Fut>(&mut self, policy: RetryPolicy, f: F) -> Result<T>
+    where
+        F: FnMut(SchedulerGRPCClient, ObservatoryClient) -> Fut,
+        Fut: Future<Output = tonic::Result<T>>,
+    {
+        self.call_with(default_backoff(), policy, f).await
+    }
+
+    /// Make a call and retry it according to `policy`, rebuilding the channel
+    /// if the transport broke.
+    ///
+    /// If the client manages the compute token, it is refreshed before the call
+    /// when it expires soon, and once more if the scheduler rejects it. The
+    /// server certificate is re-fetched once if the scheduler presents another
+    /// certificate than the pinned one.
+    ///
+    /// `f` builds and sends the request on the given clients, it is called
+    /// again for every attempt.
+    async fn call_with<T, B, F, Fut>(
+        &mut self,
+        backoff: B,
+        policy: RetryPolicy,
+        mut f: F,
+    ) -> Result<T>
+    where
+        B: Backoff,
+        F: FnMut(SchedulerGRPCClient, ObservatoryClient) -> Fut,
+        Fut: Future<Output = tonic::Result<T>>,
+    {
+        if let Some(token_source) = &self.token_source {
+            token_source.refresh_if_expiring().await?;
+        }
+        let mut token_renewed = false;
+        let mut certificate_refetched = false;
+        let result = retry!(
+            backoff,
+            async {
+                match f(
+                    self.scheduler_client.clone(),
+                    self.observability_client.clone(),
+                )
+                .await
+                {
+                    Ok(r) => utils::OperationResult::Ok(r),
+                    Err(s) => match &self.token_source {
+                        Some(token_source)
+                            if s.code() == Code::Unauthenticated && !token_renewed =>
+                        {
+                            token_renewed = true;
+                            token_source.invalidate();
+                            match token_source.refresh_if_expiring().await {
+                                Ok(()) => utils::OperationResult::Retry(s),
+                                Err(e) => {
+                                    tracing::debug!("Failed to renew compute token: {e:?}");
+                                    utils::OperationResult::Err(s)
+                                },
+                            }
+                        },
+                        _ if is_certificate_error(&s) => {
+                            if certificate_refetched {
+                                return utils::OperationResult::Err(s);
+                            }
+                            certificate_refetched = true;
+                            match self.rotate_server_certificate().await {
+                                Ok(true) => utils::OperationResult::Retry(s),
+                                Ok(false) => {
+                                    utils::OperationResult::Err(certificate_mismatch(&self.address))
+                                },
+                                Err(e) => {
+                                    tracing::debug!("Failed to rotate server certificate: {e:?}");
+                                    utils::OperationResult::Err(s)
+                                },
+                            }
+                        },
+                        _ if policy.should_retry(&s) => {
+                            if is_transport_error(&s)
+                                && let Err(e) = self.reconnect().await
+                            {
+                                tracing::debug!("Failed to reconnect to scheduler: {e:?}");
+                            }
+                            utils::OperationResult::Retry(s)
+                        },
+                        _ => utils::OperationResult::Err(s),
+                    },
+                }
+            },
+            tokio::time::sleep
+        )
+        .await;
+        Ok(result?)
+    }
+}
+
 #[pymethods]
 impl SchedulerClient {
     #[new]
@@ -65,24 +439,59 @@ impl SchedulerClient {
         address: &str,
         client_options: ClientOptions,
     ) -> std::result::Result<SchedulerClient, PyErr> {
-        let channel =
-            RUNTIME.block_on(
-                py,
-                async move { get_channel(address, client_options).await },
-            )??;
-        let scheduler_client =
-            ClientServiceClient::with_interceptor(channel.clone(), version_interceptor as _)
-                .max_encoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED)
-                .max_decoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED);
+        let compression = client_options.compression();
+        let (scheduler_client, observability_client) =
+            RUNTIME.block_on(py, Self::connect(address, client_options.clone(), None))??;
 
-        let observability_client =
-            QueryProfileServiceClient::with_interceptor(channel, version_interceptor as _)
-                .max_encoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED)
-                .max_decoding_message_size(MAX_MESSAGE_LENGTH_UNLIMITED);
+        Ok(SchedulerClient {
+            address: address.to_string(),
+            client_options,
+            token_source: None,
+            scheduler_client,
+            observability_client,
+            compression,
+        })
+    }
+
+    /// Connect to the scheduler of a direct-mode cluster of the workspace.
+    ///
+    /// The address and certificate of the cluster are looked up with `client`,
+    /// which also fetches the compute token. The token is cached and refreshed
+    /// before it expires, so the methods need no `token`.
+    #[staticmethod]
+    #[pyo3(signature = (client, workspace_id, compute_id, client_options = None))]
+    pub fn for_compute(
+        py: Python<'_>,
+        mut client: PyRefMut<'_, WrappedAPIClient>,
+        workspace_id: Uuid,
+        compute_id: Uuid,
+        client_options: Option<ClientOptions>,
+    ) -> Result<SchedulerClient> {
+        let server_info = client.get_compute_server_info(py, workspace_id, compute_id)?;
+        let mut client_options = client_options.unwrap_or_default();
+        client_options.public_server_crt = Some(server_info.public_server_key.into_bytes());
+        if client_options.tls_certificate.is_none()
+            && let Some(keypair) = client.load_client_keypair(compute_id)?
+        {
+            client_options.tls_certificate = Some(keypair.certificate_pem.into_bytes());
+            client_options.tls_private_key = Some(keypair.private_key_pem.into_bytes());
+        }
+        let address = format!("{}:{DEFAULT_SCHEDULER_PORT}", server_info.public_address);
+
+        let token_source = ComputeTokenSource::new(&client, workspace_id, compute_id);
+        let compression = client_options.compression();
+        let (scheduler_client, observability_client) = RUNTIME.block_on(py, async {
+            token_source.refresh_if_expiring().await?;
+            Self::connect(&address, client_options.clone(), Some(token_source.clone())).await
+        })??;
 
         Ok(SchedulerClient {
+            address,
+            client_options,
+            token_source: Some(token_source),
             scheduler_client,
             observability_client,
+            compression,
         })
     }
 
@@ -92,13 +501,15 @@ impl SchedulerClient {
         query_id: Uuid,
         token: Option<String>,
     ) -> Result<()> {
-        RUNTIME.block_on(py, async move {
-            let query_id = QueryIdentifier::from(query_id);
-            let mut req = Request::new(query_id.into());
-            req = insert_auth_token(req, token);
-            let _result = self.scheduler_client.cancel_query(req).await?;
-            Ok(())
-        })?
+        let query_id = QueryIdentifier::from(query_id);
+        RUNTIME.block_on(
+            py,
+            self.call(RetryPolicy::Idempotent, |mut client, _| {
+                let mut req = Request::new(query_id.into());
+                req = insert_auth_token(req, token.clone());
+                async move { client.cancel_query(req).await.map(|_| ()) }
+            }),
+        )?
     }
 
     pub fn get_direct_query_status(
@@ -109,22 +520,84 @@ impl SchedulerClient {
     ) -> Result<QueryStatusCodeSchema> {
         let query_id = QueryIdentifier::from(query_id);
 
-        let result = RUNTIME.block_on(py, async move {
-            let mut req = Request::new(query_id.into());
-            req = insert_auth_token(req, token);
-            let result = self.scheduler_client.get_query_status(req).await?;
-            Ok::<_, ApiError>(QueryStatus::from(result.into_inner()))
-        })??;
-        match result {
-            QueryStatus::Unspecified => Err(ApiError::PyErr(PyRuntimeError::new_err(
-                "Server returned unknown query status code",
-            ))),
-            QueryStatus::Scheduled => Ok(QueryStatusCodeSchema::Scheduled),
-            QueryStatus::InProgress => Ok(QueryStatusCodeSchema::InProgress),
-            QueryStatus::Success => Ok(QueryStatusCodeSchema::Success),
-            QueryStatus::Failed => Ok(QueryStatusCodeSchema::Failed),
-            QueryStatus::Canceled => Ok(QueryStatusCodeSchema::Canceled),
-        }
+        let result = RUNTIME.block_on(
+            py,
+            self.call(RetryPolicy::Idempotent, |mut client, _| {
+                let mut req = Request::new(query_id.into());
+                req = insert_auth_token(req, token.clone());
+                async move {
+                    let result = client.get_query_status(req).await?;
+                    Ok(QueryStatus::from(result.into_inner()))
+                }
+            }),
+        )??;
+        query_status_to_py(result)
+    }
+
+    /// Stream the result of a query in chunks, starting at chunk `start_chunk`.
+    #[pyo3(signature = (query_id, token, start_chunk = 0, max_chunk_bytes = None))]
+    pub fn fetch_direct_query_result(
+        &mut self,
+        py: Python<'_>,
+        query_id: Uuid,
+        token: Option<String>,
+        start_chunk: u64,
+        max_chunk_bytes: Option<u64>,
+    ) -> Result<ResultChunkIterator> {
+        let request = FetchResultRequest {
+            query_id: query_id.into(),
+            start_chunk,
+            max_chunk_bytes,
+        };
+
+        let chunks = RUNTIME.block_on(
+            py,
+            self.call(RetryPolicy::Idempotent, |mut client, _| {
+                let mut req = Request::new(request.into());
+                req = insert_auth_token(req, token.clone());
+                async move {
+                    let response = client.fetch_result(req).await?;
+                    Ok(response
+                        .into_inner()
+                        .map(|chunk| chunk.map(ResultChunk::from))
+                        .boxed())
+                }
+            }),
+        )??;
+        Ok(ResultChunkIterator {
+            chunks: Mutex::new(chunks),
+            total_chunks: None,
+            next_chunk: start_chunk,
+            received_bytes: 0,
+        })
+    }
+
+    /// Watch the status and stage progress of a query instead of polling it.
+    pub fn watch_direct_query(
+        &mut self,
+        py: Python<'_>,
+        query_id: Uuid,
+        token: Option<String>,
+    ) -> Result<QueryWatchIterator> {
+        let query_id = QueryIdentifier::from(query_id);
+
+        let events = RUNTIME.block_on(
+            py,
+            self.call(RetryPolicy::Idempotent, |mut client, _| {
+                let mut req = Request::new(query_id.into());
+                req = insert_auth_token(req, token.clone());
+                async move {
+                    let response = client.watch_query(req).await?;
+                    Ok(response
+                        .into_inner()
+                        .map(|event| event.map(QueryWatchEvent::from))
+                        .boxed())
+                }
+            }),
+        )??;
+        Ok(QueryWatchIterator {
+            events: Mutex::new(events),
+        })
     }
 
     pub fn get_direct_query_result(
@@ -136,12 +609,17 @@ impl SchedulerClient {
         let query_id = QueryIdentifier::from(query_id);
 
         RUNTIME
-            .block_on(py, async move {
-                let mut req = Request::new(query_id.into());
-                req = insert_auth_token(req, token);
-                let result = self.scheduler_client.get_query_result(req).await?;
-                Ok(result.into_inner().into())
-            })?
+            .block_on(
+                py,
+                self.call(RetryPolicy::Idempotent, |mut client, _| {
+                    let mut req = Request::new(query_id.into());
+                    req = insert_auth_token(req, token.clone());
+                    async move {
+                        let result = client.get_query_result(req).await?;
+                        Ok(result.into_inner().into())
+                    }
+                }),
+            )?
             .map(
                 |GetQueryResultResponse {
                      result,
@@ -159,22 +637,40 @@ impl SchedulerClient {
         token: Option<String>,
         labels: Option<Vec<String>>,
     ) -> Result<Uuid> {
-        let request = SubmitQueryRequest {
+        let request = SubmitQueryRequestProto::from(SubmitQueryRequest {
             query_info: QueryInfo {
                 labels: labels.unwrap_or_default(),
             },
             plan: plan.into(),
             query_settings: settings.into(),
-        };
+        });
+        let encoded_len = request.encoded_len();
 
-        RUNTIME
-            .block_on(py, async move {
-                let mut req = Request::new(request.into());
-                req = insert_auth_token(req, token);
-                let result = self.scheduler_client.submit_query(req).await?;
-                Ok(result.into_inner())
-            })?
-            .map(|response| QueryIdentifier::from(response).inner)
+        loop {
+            let encoding = self.compression.encoding_for(encoded_len);
+            let result = RUNTIME.block_on(
+                py,
+                self.call(RetryPolicy::Submission, |client, _| {
+                    let mut req = Request::new(request.clone());
+                    req = insert_auth_token(req, token.clone());
+                    let mut client = match encoding {
+                        Some(encoding) => client.send_compressed(encoding),
+                        None => client,
+                    };
+                    async move { Ok(client.submit_query(req).await?.into_inner()) }
+                }),
+            )?;
+            // Send the plan again if the scheduler does not accept the compression.
+            match result {
+                Err(ApiError::GRPCError(status)) if self.compression.renegotiate(&status) => {
+                    tracing::debug!(
+                        "Scheduler rejected the gRPC compression: {}",
+                        status.message()
+                    );
+                },
+                result => return result.map(|response| QueryIdentifier::from(response).inner),
+            }
+        }
     }
 
     pub fn get_direct_query_profile(
@@ -184,21 +680,23 @@ impl SchedulerClient {
         tag: Option<Vec<u8>>,
         token: Option<String>,
     ) -> Result<Option<QueryProfilePy>> {
-        RUNTIME
-            .block_on(py, async move {
-                let query_id = QueryIdentifier::from(query_id);
+        let query_id = QueryIdentifier::from(query_id);
 
-                let mut req = Request::new(
-                    GetQueryProfileRequest {
-                        query_id,
-                        tag: tag.map(Into::into),
-                    }
-                    .into(),
-                );
-                req = insert_auth_token(req, token);
-                let response = self.observability_client.get_query_profile(req).await?;
-                Ok(response.into_inner().into())
-            })?
+        RUNTIME
+            .block_on(
+                py,
+                self.call(RetryPolicy::Idempotent, |_, mut client| {
+                    let mut req = Request::new(
+                        GetQueryProfileRequest {
+                            query_id,
+                            tag: tag.clone().map(Into::into),
+                        }
+                        .into(),
+                    );
+                    req = insert_auth_token(req, token.clone());
+                    async move { Ok(client.get_query_profile(req).await?.into_inner().into()) }
+                }),
+            )?
             .map(|response: Option<QueryProfile>| {
                 response.map(|profile| query_profile_to_py(py, profile))
             })
@@ -214,11 +712,14 @@ impl SchedulerClient {
         ir: bool,
     ) -> Result<QueryPlansPy> {
         let plans = PlanSelection { ir, phys };
+        // The scheduler is unavailable until the plans are ready, so keep polling.
         let query_plans: QueryPlans = RUNTIME
-            .block_on(py, async move {
-                retry!(
+            .block_on(
+                py,
+                self.call_with(
                     Fixed::new(Duration::from_millis(50)),
-                    async {
+                    RetryPolicy::Idempotent,
+                    |mut client, _| {
                         let mut req = Request::new(
                             GetQueryPlansRequest {
                                 query_id: query_id.into(),
@@ -227,18 +728,10 @@ impl SchedulerClient {
                             .into(),
                         );
                         req = insert_auth_token(req, token.clone());
-                        match self.scheduler_client.get_query_plans(req).await {
-                            Ok(r) => utils::OperationResult::Ok(r),
-                            Err(s) if s.code() == Code::Unavailable => {
-                                utils::OperationResult::Retry(s)
-                            },
-                            Err(s) => utils::OperationResult::Err(s),
-                        }
+                        async move { client.get_query_plans(req).await }
                     },
-                    tokio::time::sleep
-                )
-                .await
-            })??
+                ),
+            )??
             .into_inner()
             .into();
 
@@ -281,6 +774,12 @@ pub struct ClientOptions {
     pub tls_private_key: Option<Vec<u8>>,
     #[pyo3(get, set)]
     pub insecure: bool,
+    /// Compression of large messages, `None` to send them uncompressed.
+    #[pyo3(get, set)]
+    pub compression: Option<GrpcCompression>,
+    /// Messages smaller than this many bytes are sent uncompressed.
+    #[pyo3(get, set)]
+    pub compression_threshold: Option<usize>,
 }
 
 #[pymethods]
@@ -291,6 +790,17 @@ impl ClientOptions {
     }
 }
 
+impl ClientOptions {
+    fn compression(&self) -> Compression {
+        GrpcCompression::settings(self.compression, self.compression_threshold)
+    }
+}
+
+/// Interval of the HTTP/2 pings that keep idle connections to the scheduler
+/// open through load balancers.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[allow(clippy::result_large_err)]
 async fn get_channel(address: &str, client_options: ClientOptions) -> Result<Channel> {
     let uri_builder = Uri::builder().authority(address).path_and_query("/");
@@ -328,12 +838,21 @@ async fn get_channel(address: &str, client_options: ClientOptions) -> Result<Cha
         async {
             let res = endpoint
                 .clone()
+                .http2_keep_alive_interval(KEEP_ALIVE_INTERVAL)
+                .keep_alive_timeout(KEEP_ALIVE_TIMEOUT)
+                .keep_alive_while_idle(true)
+                .tcp_keepalive(Some(KEEP_ALIVE_INTERVAL))
                 .user_agent(user_agent(VERSIONS.get().unwrap().as_ref().map(|(_, versions)| versions)))
                 .unwrap()
                 .connect()
-                .await?;
-
-            Ok::<_, ApiError>(res)
+                .await;
+
+            match res {
+                Ok(channel) => utils::OperationResult::Ok(channel),
+                // An untrusted certificate does not become trusted by retrying.
+                Err(e) if is_certificate_error(&e) => utils::OperationResult::Err(ApiError::from(e)),
+                Err(e) => utils::OperationResult::Retry(ApiError::from(e)),
+            }
         },
         tokio::time::sleep
     }
@@ -0,0 +1,219 @@
+#![allow(clippy::result_large_err)]
+
+use std::time::{Duration, Instant};
+
+use polars_axum_models::{DBClusterModeSchema, QueryStatusCodeSchema};
+use pyo3::exceptions::{PyRuntimeError, PyTimeoutError};
+use pyo3::pyclass::boolean_struct::False;
+use pyo3::{Py, PyAny, PyClass, PyRefMut, Python, pyclass, pymethods};
+use uuid::Uuid;
+
+use crate::client::WrappedAPIClient;
+use crate::constants::RUNTIME;
+use crate::error::{ApiError, Result};
+use crate::query_grpc::{PlanFormatPy, QueryPlansPy, SchedulerClient};
+use crate::serde_types::{QueryInfoPy, QueryProfilePy};
+
+/// Upper bound of the interval between two status requests while waiting.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How a query was submitted, and so how it is reached afterwards.
+enum Transport {
+    /// Through the control plane, with the credentials of the API client.
+    Proxy {
+        client: Py<WrappedAPIClient>,
+        workspace_id: Uuid,
+    },
+    /// Directly to the scheduler of the cluster.
+    Direct {
+        client: Py<SchedulerClient>,
+        /// Callable returning the current compute token, `None` for clusters
+        /// without authentication.
+        token: Option<Py<PyAny>>,
+    },
+}
+
+/// A submitted query, independent of the mode of the cluster it runs on.
+#[pyclass(frozen)]
+pub struct QueryHandle {
+    query_id: Uuid,
+    transport: Transport,
+}
+
+fn is_done(status: &QueryStatusCodeSchema) -> bool {
+    matches!(
+        status,
+        QueryStatusCodeSchema::Success
+            | QueryStatusCodeSchema::Failed
+            | QueryStatusCodeSchema::Canceled
+    )
+}
+
+/// Mutably borrow the client of a handle.
+///
+/// The same client is shared by all handles of a session, so it may already be
+/// borrowed, e.g. by a handle that is polled from another thread.
+fn borrow_client<'py, T: PyClass<Frozen = False>>(
+    py: Python<'py>,
+    client: &'py Py<T>,
+) -> Result<PyRefMut<'py, T>> {
+    client.try_borrow_mut(py).map_err(|_| {
+        ApiError::PyErr(PyRuntimeError::new_err(
+            "The client is in use by another query, try again once it is done",
+        ))
+    })
+}
+
+fn current_token(py: Python<'_>, token: &Option<Py<PyAny>>) -> Result<Option<String>> {
+    match token {
+        Some(token) => Ok(token.call0(py)?.extract(py)?),
+        None => Ok(None),
+    }
+}
+
+#[pymethods]
+impl QueryHandle {
+    /// Handle of a query submitted through the control plane.
+    #[staticmethod]
+    fn proxy(client: Py<WrappedAPIClient>, workspace_id: Uuid, query_id: Uuid) -> Self {
+        QueryHandle {
+            query_id,
+            transport: Transport::Proxy {
+                client,
+                workspace_id,
+            },
+        }
+    }
+
+    /// Handle of a query submitted directly to a cluster.
+    ///
+    /// `token` is called before every request for the current compute token.
+    #[staticmethod]
+    #[pyo3(signature = (client, query_id, token = None))]
+    fn direct(client: Py<SchedulerClient>, query_id: Uuid, token: Option<Py<PyAny>>) -> Self {
+        QueryHandle {
+            query_id,
+            transport: Transport::Direct { client, token },
+        }
+    }
+
+    #[getter]
+    fn query_id(&self) -> Uuid {
+        self.query_id
+    }
+
+    #[getter]
+    fn mode(&self) -> DBClusterModeSchema {
+        match self.transport {
+            Transport::Proxy { .. } => DBClusterModeSchema::Proxy,
+            Transport::Direct { .. } => DBClusterModeSchema::Direct,
+        }
+    }
+
+    fn status(&self, py: Python<'_>) -> Result<QueryStatusCodeSchema> {
+        match &self.transport {
+            Transport::Proxy {
+                client,
+                workspace_id,
+            } => {
+                let query =
+                    borrow_client(py, client)?.get_query(py, *workspace_id, self.query_id)?;
+                Ok(query.state_timing.latest_status)
+            },
+            Transport::Direct { client, token } => {
+                let token = current_token(py, token)?;
+                borrow_client(py, client)?.get_direct_query_status(py, self.query_id, token)
+            },
+        }
+    }
+
+    /// Poll the status until the query is done and return the final status.
+    #[pyo3(signature = (timeout = None))]
+    fn wait(&self, py: Python<'_>, timeout: Option<Duration>) -> Result<QueryStatusCodeSchema> {
+        let start = Instant::now();
+        let mut interval = Duration::from_millis(50);
+        loop {
+            let status = self.status(py)?;
+            if is_done(&status) {
+                return Ok(status);
+            }
+            if let Some(timeout) = timeout
+                && start.elapsed() >= timeout
+            {
+                return Err(ApiError::PyErr(PyTimeoutError::new_err(format!(
+                    "Query {} is not done after {timeout:?}",
+                    self.query_id
+                ))));
+            }
+            RUNTIME.block_on(py, tokio::time::sleep(interval))?;
+            interval = interval.mul_f64(1.5).min(MAX_POLL_INTERVAL);
+        }
+    }
+
+    /// Wait for the query to be done and return its result.
+    #[pyo3(signature = (timeout = None))]
+    fn result(&self, py: Python<'_>, timeout: Option<Duration>) -> Result<QueryInfoPy> {
+        self.wait(py, timeout)?;
+        match &self.transport {
+            Transport::Proxy { client, .. } => {
+                borrow_client(py, client)?.get_query_result(py, self.query_id)
+            },
+            Transport::Direct { client, token } => {
+                let token = current_token(py, token)?;
+                borrow_client(py, client)?.get_direct_query_result(py, self.query_id, token)
+            },
+        }
+    }
+
+    fn cancel(&self, py: Python<'_>) -> Result<()> {
+        match &self.transport {
+            Transport::Proxy {
+                client,
+                workspace_id,
+            } => borrow_client(py, client)?.cancel_proxy_query(py, *workspace_id, self.query_id),
+            Transport::Direct { client, token } => {
+                let token = current_token(py, token)?;
+                borrow_client(py, client)?.cancel_direct_query(py, self.query_id, token)
+            },
+        }
+    }
+
+    #[pyo3(signature = (phys = false, ir = false))]
+    fn plans(&self, py: Python<'_>, phys: bool, ir: bool) -> Result<QueryPlansPy> {
+        match &self.transport {
+            Transport::Proxy {
+                client,
+                workspace_id,
+            } => {
+                let plans =
+                    borrow_client(py, client)?.get_query_plans(py, *workspace_id, self.query_id)?;
+                // The control plane keeps the plans as dot graphs
+                Ok(QueryPlansPy {
+                    format: PlanFormatPy::Dot,
+                    ir_plan: plans.ir_plan.filter(|_| ir),
+                    phys_plan: plans.phys_plan.filter(|_| phys),
+                })
+            },
+            Transport::Direct { client, token } => {
+                let token = current_token(py, token)?;
+                borrow_client(py, client)?.get_direct_query_plan(py, self.query_id, token, phys, ir)
+            },
+        }
+    }
+
+    /// The profile of the query, `None` if it did not change since `tag`.
+    #[pyo3(signature = (tag = None))]
+    fn profile(&self, py: Python<'_>, tag: Option<Vec<u8>>) -> Result<Option<QueryProfilePy>> {
+        match &self.transport {
+            // Profiles are streamed by the scheduler, the control plane does not keep them
+            Transport::Proxy { .. } => Err(ApiError::PyErr(PyRuntimeError::new_err(format!(
+                "Query {} runs in proxy mode, query profiles are only available in direct mode",
+                self.query_id
+            )))),
+            Transport::Direct { client, token } => {
+                let token = current_token(py, token)?;
+                borrow_client(py, client)?.get_direct_query_profile(py, self.query_id, tag, token)
+            },
+        }
+    }
+}
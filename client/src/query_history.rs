@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    Int32Builder, Int64Builder, ListBuilder, StringBuilder, TimestampMillisecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use chrono::{DateTime, Utc};
+use polars_axum_models::QueryWithStateTimingAndResultSchema;
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+}
+
+fn duration_ms(from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Option<i64> {
+    Some((to? - from?).num_milliseconds())
+}
+
+fn query_history_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("query_id", DataType::Utf8, false),
+        Field::new("workspace_id", DataType::Utf8, false),
+        Field::new("cluster_id", DataType::Utf8, false),
+        Field::new("user_id", DataType::Utf8, false),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("request_time", timestamp_type(), false),
+        Field::new("started_at", timestamp_type(), true),
+        Field::new("ended_at", timestamp_type(), true),
+        Field::new("latest_status_time", timestamp_type(), false),
+        Field::new("queued_ms", DataType::Int64, true),
+        Field::new("running_ms", DataType::Int64, true),
+        Field::new("total_ms", DataType::Int64, true),
+        Field::new("total_stages", DataType::Int32, true),
+        Field::new("finished_stages", DataType::Int32, true),
+        Field::new("failed_stages", DataType::Int32, true),
+        Field::new("n_rows_result", DataType::Int64, true),
+        Field::new("file_type_sink", DataType::Utf8, true),
+        Field::new(
+            "errors",
+            DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true))),
+            true,
+        ),
+    ])
+}
+
+/// Convert query history into an Arrow IPC stream with a single record batch.
+///
+/// Durations are derived from the state timings: `queued_ms` runs from the
+/// request until the query started, `running_ms` from start to end and
+/// `total_ms` from the request until the end. The result statistics are null
+/// for queries that were fetched without their result.
+pub(crate) fn query_history_to_ipc(
+    rows: &[QueryWithStateTimingAndResultSchema],
+) -> Result<Vec<u8>, ArrowError> {
+    let n = rows.len();
+    let mut query_id = StringBuilder::with_capacity(n, n * 36);
+    let mut workspace_id = StringBuilder::with_capacity(n, n * 36);
+    let mut cluster_id = StringBuilder::with_capacity(n, n * 36);
+    let mut user_id = StringBuilder::with_capacity(n, n * 36);
+    let mut status = StringBuilder::new();
+    let mut request_time = TimestampMillisecondBuilder::with_capacity(n).with_timezone("UTC");
+    let mut started_at = TimestampMillisecondBuilder::with_capacity(n).with_timezone("UTC");
+    let mut ended_at = TimestampMillisecondBuilder::with_capacity(n).with_timezone("UTC");
+    let mut latest_status_time = TimestampMillisecondBuilder::with_capacity(n).with_timezone("UTC");
+    let mut queued_ms = Int64Builder::with_capacity(n);
+    let mut running_ms = Int64Builder::with_capacity(n);
+    let mut total_ms = Int64Builder::with_capacity(n);
+    let mut total_stages = Int32Builder::with_capacity(n);
+    let mut finished_stages = Int32Builder::with_capacity(n);
+    let mut failed_stages = Int32Builder::with_capacity(n);
+    let mut n_rows_result = Int64Builder::with_capacity(n);
+    let mut file_type_sink = StringBuilder::new();
+    let mut errors = ListBuilder::new(StringBuilder::new());
+
+    for row in rows {
+        let query = &row.query;
+        let timing = &row.state_timing;
+        query_id.append_value(query.id.to_string());
+        workspace_id.append_value(query.workspace_id.to_string());
+        cluster_id.append_value(query.cluster_id.to_string());
+        user_id.append_value(query.user_id.to_string());
+        status.append_value(timing.latest_status.to_string());
+        request_time.append_value(query.request_time.timestamp_millis());
+        started_at.append_option(timing.started_at.map(|t| t.timestamp_millis()));
+        ended_at.append_option(timing.ended_at.map(|t| t.timestamp_millis()));
+        latest_status_time.append_value(timing.latest_status_time.timestamp_millis());
+        queued_ms.append_option(duration_ms(Some(query.request_time), timing.started_at));
+        running_ms.append_option(duration_ms(timing.started_at, timing.ended_at));
+        total_ms.append_option(duration_ms(Some(query.request_time), timing.ended_at));
+
+        match &row.result {
+            Some(result) => {
+                total_stages.append_value(result.total_stages);
+                finished_stages.append_value(result.finished_stages);
+                failed_stages.append_value(result.failed_stages);
+                n_rows_result.append_option(result.n_rows_result);
+                file_type_sink.append_option(
+                    result
+                        .file_type_sink
+                        .as_ref()
+                        .map(|file_type| format!("{file_type:?}")),
+                );
+                for error in &result.errors {
+                    errors.values().append_value(error);
+                }
+                errors.append(true);
+            },
+            None => {
+                total_stages.append_null();
+                finished_stages.append_null();
+                failed_stages.append_null();
+                n_rows_result.append_null();
+                file_type_sink.append_null();
+                errors.append(false);
+            },
+        }
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(query_id.finish()),
+        Arc::new(workspace_id.finish()),
+        Arc::new(cluster_id.finish()),
+        Arc::new(user_id.finish()),
+        Arc::new(status.finish()),
+        Arc::new(request_time.finish()),
+        Arc::new(started_at.finish()),
+        Arc::new(ended_at.finish()),
+        Arc::new(latest_status_time.finish()),
+        Arc::new(queued_ms.finish()),
+        Arc::new(running_ms.finish()),
+        Arc::new(total_ms.finish()),
+        Arc::new(total_stages.finish()),
+        Arc::new(finished_stages.finish()),
+        Arc::new(failed_stages.finish()),
+        Arc::new(n_rows_result.finish()),
+        Arc::new(file_type_sink.finish()),
+        Arc::new(errors.finish()),
+    ];
+    let schema = Arc::new(query_history_schema());
+    let batch = RecordBatch::try_new(schema.clone(), columns)?;
+
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema)?;
+    writer.write(&batch)?;
+    writer.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow_array::{Array, Int64Array};
+    use arrow_ipc::reader::StreamReader;
+    use chrono::TimeZone;
+    use polars_axum_models::{
+        QuerySchema, QueryStateTimingSchema, QueryStatusCodeSchema, ResultSchema,
+    };
+
+    use super::*;
+
+    fn row(
+        started_at: Option<DateTime<Utc>>,
+        ended_at: Option<DateTime<Utc>>,
+        result: Option<ResultSchema>,
+    ) -> QueryWithStateTimingAndResultSchema {
+        let request_time = Utc.timestamp_millis_opt(1_000).unwrap();
+        let latest_status_time = ended_at.or(started_at).unwrap_or(request_time);
+        QueryWithStateTimingAndResultSchema {
+            query: QuerySchema {
+                request_time,
+                ..Default::default()
+            },
+            state_timing: QueryStateTimingSchema {
+                final_known_state: None,
+                final_status_time: None,
+                last_known_state: QueryStatusCodeSchema::Queued,
+                last_known_status_time: latest_status_time,
+                last_progress_time: None,
+                latest_status: QueryStatusCodeSchema::Queued,
+                latest_status_time,
+                started_at,
+                ended_at,
+            },
+            result,
+        }
+    }
+
+    fn int64_column(batch: &RecordBatch, name: &str) -> Vec<Option<i64>> {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_query_history_to_ipc() {
+        let at = |millis| Some(Utc.timestamp_millis_opt(millis).unwrap());
+        let rows = vec![
+            row(
+                at(1_500),
+                at(4_000),
+                Some(ResultSchema {
+                    total_stages: 3,
+                    finished_stages: 3,
+                    failed_stages: 0,
+                    n_rows_result: Some(10),
+                    file_type_sink: None,
+                    errors: vec![],
+                }),
+            ),
+            row(at(2_000), None, None),
+            row(None, None, None),
+        ];
+
+        let ipc = query_history_to_ipc(&rows).unwrap();
+        let mut reader = StreamReader::try_new(Cursor::new(ipc), None).unwrap();
+        assert_eq!(reader.schema().as_ref(), &query_history_schema());
+        assert_eq!(reader.schema().fields().len(), 18);
+
+        let batch = reader.next().unwrap().unwrap();
+        assert!(reader.next().is_none());
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(
+            int64_column(&batch, "queued_ms"),
+            [Some(500), Some(1_000), None]
+        );
+        assert_eq!(
+            int64_column(&batch, "running_ms"),
+            [Some(2_500), None, None]
+        );
+        assert_eq!(int64_column(&batch, "total_ms"), [Some(3_000), None, None]);
+        assert_eq!(
+            int64_column(&batch, "n_rows_result"),
+            [Some(10), None, None]
+        );
+        assert_eq!(batch.column_by_name("errors").unwrap().null_count(), 2);
+    }
+}
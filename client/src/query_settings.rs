@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use protos_client_compute::client::{
-    Engine, GraphFormat, QuerySettings, QueryType, ShuffleCompression, ShuffleFormat, ShuffleOpts,
+    Engine, GraphFormat, QueryPriority, QuerySettings, QueryType, ShuffleCompression,
+    ShuffleFormat, ShuffleOpts,
 };
 use pyo3::exceptions::PyValueError;
 use pyo3::{PyResult, pyclass};
@@ -91,6 +94,13 @@ pub enum PyShuffleFormat {
     Parquet,
 }
 
+#[pyclass]
+#[derive(Debug, Clone, Copy)]
+pub enum PyQueryPriority {
+    Batch,
+    Interactive,
+}
+
 #[pyclass]
 #[derive(Debug, Clone)]
 pub struct PyQuerySettings {
@@ -100,6 +110,11 @@ pub struct PyQuerySettings {
     pub prefer_dot: bool,
     /// Number of retries on failed tasks
     pub n_retries: u32,
+    /// Wall-clock time after which the scheduler cancels the query
+    pub timeout: Option<Duration>,
+    pub priority: PyQueryPriority,
+    /// Soft limit in bytes on the memory of the query, above which it spills to disk
+    pub memory_limit: Option<u64>,
 }
 
 impl From<PyQuerySettings> for QuerySettings {
@@ -113,6 +128,18 @@ impl From<PyQuerySettings> for QuerySettings {
             },
             n_retries: value.n_retries,
             query_type: value.query_type.into(),
+            timeout: value.timeout,
+            priority: value.priority.into(),
+            memory_limit: value.memory_limit,
+        }
+    }
+}
+
+impl From<PyQueryPriority> for QueryPriority {
+    fn from(value: PyQueryPriority) -> Self {
+        match value {
+            PyQueryPriority::Batch => Self::Batch,
+            PyQueryPriority::Interactive => Self::Interactive,
         }
     }
 }
@@ -1,15 +1,18 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use protos_client_compute::client::{ComputeQueryInfo, StageStatistics};
 use protos_client_compute::observatory::QueryProfile;
 use protos_common::query_info::FileType;
-use protos_common::{QueryOutput, QueryResult};
+use protos_common::{QueryLimit, QueryOutput, QueryResult};
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pyclass;
 use pyo3::types::PyBytes;
 
-use crate::query_settings::{PyEngine, PyQuerySettings, PyQueryType, PyShuffleOpts};
+use crate::query_settings::{
+    PyEngine, PyQueryPriority, PyQuerySettings, PyQueryType, PyShuffleOpts,
+};
 
 #[derive(FromPyObject)]
 pub struct DistributedSettings {
@@ -18,16 +21,24 @@ pub struct DistributedSettings {
     equi_join_broadcast_limit: u64,
 }
 
+#[derive(FromPyObject)]
+pub struct QueryLimits {
+    timeout: Option<Duration>,
+    priority: String,
+    memory_limit: Option<u64>,
+}
+
 #[allow(clippy::needless_lifetimes)]
 #[allow(clippy::too_many_arguments)]
 #[pyfunction]
-#[pyo3(signature=(*, engine, prefer_dot, shuffle_opts, n_retries, distributed_settings))]
+#[pyo3(signature=(*, engine, prefer_dot, shuffle_opts, n_retries, distributed_settings, limits=None))]
 pub fn serialize_query_settings(
     engine: &str,
     prefer_dot: bool,
     shuffle_opts: PyShuffleOpts,
     n_retries: u32,
     distributed_settings: Option<DistributedSettings>,
+    limits: Option<QueryLimits>,
 ) -> PyResult<PyQuerySettings> {
     let query_type = match distributed_settings {
         None => PyQueryType::Single(),
@@ -51,11 +62,29 @@ pub fn serialize_query_settings(
         },
     };
 
+    let (timeout, priority, memory_limit) = match limits {
+        None => (None, PyQueryPriority::Batch, None),
+        Some(limits) => {
+            let priority = match limits.priority.as_str() {
+                "batch" => PyQueryPriority::Batch,
+                "interactive" => PyQueryPriority::Interactive,
+                v => {
+                    let msg = format!("expected one of {{'batch', 'interactive'}}, got {v}",);
+                    return Err(PyValueError::new_err(msg));
+                },
+            };
+            (limits.timeout, priority, limits.memory_limit)
+        },
+    };
+
     let settings = PyQuerySettings {
         engine,
         prefer_dot,
         n_retries,
         query_type,
+        timeout,
+        priority,
+        memory_limit,
     };
 
     Ok(settings)
@@ -95,6 +124,9 @@ pub struct QueryInfoPy {
     pub phys_plan_dot: Option<String>,
     #[pyo3(get)]
     pub stages_stats: Option<BTreeMap<u32, StageStatsPy>>,
+    /// The limits of the query settings that the query hit.
+    #[pyo3(get)]
+    pub limits_hit: Vec<String>,
 }
 #[pymethods]
 impl QueryInfoPy {
@@ -135,6 +167,14 @@ pub(crate) fn query_result_to_py(
         },
         None => "none",
     };
+    let limits_hit = query_info
+        .limits_hit
+        .iter()
+        .map(|limit| match limit {
+            QueryLimit::Timeout => "timeout".to_string(),
+            QueryLimit::Memory => "memory".to_string(),
+        })
+        .collect();
     QueryInfoPy {
         total_stages: query_info.total_stages,
         finished_stages: query_info.finished_stages,
@@ -155,6 +195,7 @@ pub(crate) fn query_result_to_py(
             ci.head
                 .map(|res| res.map(|b| PyBytes::new(py, b.as_ref()).unbind()))
         }),
+        limits_hit,
     }
 }
 
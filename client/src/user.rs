@@ -1,15 +1,108 @@
 #![allow(clippy::result_large_err)]
 
-use polars_axum_models::UserSchema;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use polars_axum_models::{OrganizationQuery, OrganizationSchema, Pagination, UserSchema};
 use polars_backend_client::client::ApiClient;
-use pyo3::{Python, pymethods};
+use pyo3::{PyErr, Python, pyclass, pymethods};
+use uuid::Uuid;
 
-use crate::client::WrappedAPIClient;
+use crate::client::{CredentialSource, WrappedAPIClient};
+use crate::constants::RUNTIME;
 use crate::error::ApiError;
 
+/// Who the client is authenticated as.
+///
+/// Holds no secrets, the access token is only identified by its fingerprint.
+/// If authenticating fails, only the settings of the profile are filled in and
+/// `error` tells why.
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct Identity {
+    /// Name of the configuration profile.
+    profile: String,
+    /// Polars Cloud domain of the profile.
+    domain: String,
+    credential_source: Option<CredentialSource>,
+    /// Subject of the access token, the id of the user or service account.
+    subject: Option<String>,
+    user_id: Option<Uuid>,
+    email: Option<String>,
+    /// Client the access token was issued to.
+    client_id: Option<String>,
+    /// Scopes granted to the access token.
+    scopes: Vec<String>,
+    organizations: Vec<OrganizationSchema>,
+    expires_at: Option<DateTime<Utc>>,
+    /// Remaining lifetime of the access token.
+    expires_in: Option<Duration>,
+    /// Short SHA-256 hash of the access token.
+    token_fingerprint: Option<String>,
+    /// Why authenticating failed, `None` if it succeeded.
+    error: Option<String>,
+}
+
+impl WrappedAPIClient {
+    /// Fill in the parts of `identity` that need a valid token.
+    fn identify(&mut self, py: Python<'_>, identity: &mut Identity) -> Result<(), ApiError> {
+        let claims = RUNTIME.block_on(py, self.tokens.claims())??;
+        identity.subject = claims.sub;
+        identity.email = claims.email;
+        identity.client_id = claims.azp;
+        identity.scopes = claims.scope;
+        identity.expires_at = Some(claims.exp);
+        identity.expires_in = Some((claims.exp - Utc::now()).to_std().unwrap_or_default());
+
+        let user = self.get_user(py)?;
+        identity.user_id = Some(user.id);
+        identity.email = identity.email.take().or(user.email);
+        identity.organizations = self.call_paginated(py, |client: &ApiClient, page: i64| {
+            let pagination = Pagination {
+                page,
+                limit: 1000,
+                offset: 0,
+            };
+            client.get_organizations(pagination, OrganizationQuery { name: None })
+        })?;
+        Ok(())
+    }
+}
+
 #[pymethods]
 impl WrappedAPIClient {
     pub fn get_user(&mut self, py: Python<'_>) -> Result<UserSchema, ApiError> {
         self.call(py, |client: &ApiClient| client.get_logged_in_user())
     }
+
+    /// The identity of the authenticated user or service account.
+    ///
+    /// Does not fail if authenticating fails, that is when it is needed most.
+    pub fn whoami(&mut self, py: Python<'_>) -> Result<Identity, ApiError> {
+        let profile = self.tokens.profile();
+        let mut identity = Identity {
+            profile: profile.name().to_string(),
+            domain: profile.domain().to_string(),
+            credential_source: None,
+            subject: None,
+            user_id: None,
+            email: None,
+            client_id: None,
+            scopes: Vec::new(),
+            organizations: Vec::new(),
+            expires_at: None,
+            expires_in: None,
+            token_fingerprint: None,
+            error: None,
+        };
+        if let Err(e) = self.identify(py, &mut identity) {
+            identity.error = Some(PyErr::from(e).to_string());
+        }
+        // Without a loaded token, the source the profile asks for is the best guess
+        identity.credential_source = RUNTIME
+            .block_on(py, self.tokens.credential_source())?
+            .or(self.tokens.profile().credential_source());
+        identity.token_fingerprint = RUNTIME.block_on(py, self.tokens.token_fingerprint())?;
+        Ok(identity)
+    }
 }
@@ -1,10 +1,11 @@
 #![allow(clippy::result_large_err)]
 
 use polars_axum_models::{
-    InstanceSpecsSchema, Pagination, WorkspaceClusterDefaultsSchema, WorkspaceQuery,
-    WorkspaceSchema,
+    InstanceSpecsSchema, Pagination, WorkspaceClusterDefaultsSchema, WorkspaceDetails,
+    WorkspaceQuery, WorkspaceSchema,
 };
 use polars_backend_client::client::ApiClient;
+use pyo3::exceptions::PyValueError;
 use pyo3::{Python, pyclass, pymethods};
 use uuid::Uuid;
 
@@ -15,12 +16,45 @@ use crate::error::ApiError;
 #[derive(Clone, Debug)]
 pub struct DefaultComputeSpecs {
     instance_type: Option<String>,
+    big_instance_type: Option<String>,
     cpus: Option<u32>,
     ram_gb: Option<u32>,
+    big_instance_multiplier: Option<u32>,
     storage: Option<i32>,
     cluster_size: i32,
 }
 
+impl From<WorkspaceClusterDefaultsSchema> for DefaultComputeSpecs {
+    fn from(defaults: WorkspaceClusterDefaultsSchema) -> Self {
+        let mut specs = DefaultComputeSpecs {
+            instance_type: None,
+            big_instance_type: None,
+            cpus: None,
+            ram_gb: None,
+            big_instance_multiplier: None,
+            storage: defaults.storage,
+            cluster_size: defaults.cluster_size,
+        };
+
+        match defaults.instance_specs {
+            InstanceSpecsSchema::InstanceType { standard, big } => {
+                specs.instance_type = Some(standard);
+                specs.big_instance_type = big;
+            },
+            InstanceSpecsSchema::Specs {
+                cpus,
+                ram_gb,
+                multiplier,
+            } => {
+                specs.cpus = Some(cpus);
+                specs.ram_gb = Some(ram_gb);
+                specs.big_instance_multiplier = multiplier;
+            },
+        }
+        specs
+    }
+}
+
 #[pymethods]
 impl WrappedAPIClient {
     #[pyo3(signature=(workspace_id))]
@@ -32,6 +66,25 @@ impl WrappedAPIClient {
         self.call(py, |client: &ApiClient| client.get_workspace(workspace_id))
     }
 
+    #[pyo3(signature=(workspace_id, *, name=None, description=None, idle_timeout_mins=None))]
+    pub fn patch_workspace_details(
+        &mut self,
+        py: Python<'_>,
+        workspace_id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        idle_timeout_mins: Option<i32>,
+    ) -> Result<(), ApiError> {
+        let details = WorkspaceDetails {
+            name,
+            description,
+            idle_timeout_mins,
+        };
+        self.call(py, |client: &ApiClient| async move {
+            client.patch_workspace_details(workspace_id, &details).await
+        })
+    }
+
     #[pyo3(signature=(workspace_id))]
     pub fn get_workspace_cluster_defaults(
         &mut self,
@@ -43,6 +96,72 @@ impl WrappedAPIClient {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature=(
+        workspace_id,
+        *,
+        cluster_size,
+        instance_type=None,
+        big_instance_type=None,
+        cpus=None,
+        ram_gb=None,
+        big_instance_multiplier=None,
+        storage=None
+    ))]
+    pub fn set_workspace_cluster_defaults(
+        &mut self,
+        py: Python<'_>,
+        workspace_id: Uuid,
+        cluster_size: i32,
+        instance_type: Option<String>,
+        big_instance_type: Option<String>,
+        cpus: Option<u32>,
+        ram_gb: Option<u32>,
+        big_instance_multiplier: Option<u32>,
+        storage: Option<i32>,
+    ) -> Result<(), ApiError> {
+        let instance_specs = match (instance_type, cpus, ram_gb) {
+            (Some(standard), None, None) if big_instance_multiplier.is_none() => {
+                InstanceSpecsSchema::InstanceType {
+                    standard,
+                    big: big_instance_type,
+                }
+            },
+            (None, Some(cpus), Some(ram_gb)) if big_instance_type.is_none() => {
+                InstanceSpecsSchema::Specs {
+                    cpus,
+                    ram_gb,
+                    multiplier: big_instance_multiplier,
+                }
+            },
+            _ => {
+                return Err(PyValueError::new_err(
+                    "Specify either `instance_type` (optionally with `big_instance_type`) or `cpus` and `ram_gb` (optionally with `big_instance_multiplier`)",
+                )
+                .into());
+            },
+        };
+        let defaults = WorkspaceClusterDefaultsSchema {
+            instance_specs,
+            storage,
+            cluster_size,
+        };
+        self.call(py, |client: &ApiClient| async move {
+            client.set_cluster_defaults(workspace_id, &defaults).await
+        })
+    }
+
+    #[pyo3(signature=(workspace_id))]
+    pub fn delete_workspace_cluster_defaults(
+        &mut self,
+        py: Python<'_>,
+        workspace_id: Uuid,
+    ) -> Result<(), ApiError> {
+        self.call(py, |client: &ApiClient| {
+            client.delete_cluster_defaults(workspace_id)
+        })
+    }
+
     #[pyo3(signature=(name=None, organization_id=None))]
     pub fn get_workspaces(
         &mut self,
@@ -72,27 +191,6 @@ impl WrappedAPIClient {
         workspace_id: Uuid,
     ) -> Result<Option<DefaultComputeSpecs>, ApiError> {
         let defaults = self.get_workspace_cluster_defaults(py, workspace_id)?;
-        let Some(defaults) = defaults else {
-            return Ok(None);
-        };
-
-        let mut specs = DefaultComputeSpecs {
-            instance_type: None,
-            cpus: None,
-            ram_gb: None,
-            storage: defaults.storage,
-            cluster_size: defaults.cluster_size,
-        };
-
-        match defaults.instance_specs {
-            InstanceSpecsSchema::InstanceType { standard, .. } => {
-                specs.instance_type = Some(standard)
-            },
-            InstanceSpecsSchema::Specs { cpus, ram_gb, .. } => {
-                specs.cpus = Some(cpus);
-                specs.ram_gb = Some(ram_gb);
-            },
-        }
-        Ok(Some(specs))
+        Ok(defaults.map(DefaultComputeSpecs::from))
     }
 }
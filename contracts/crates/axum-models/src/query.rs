@@ -7,6 +7,7 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::EntityOrdering;
+use crate::compute::csv_vec_opt;
 use crate::query_status::QueryStatusCodeSchema;
 
 #[cfg_attr(feature = "pyo3", pyclass(get_all))]
@@ -101,10 +102,27 @@ pub struct QueryWithStatusSchema {
     pub status: StatusSchema,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct QueryParamsFilter {
     pub cluster_id: Option<Uuid>,
     pub user_id: Option<Uuid>,
+    /// Only queries whose latest status is one of the given statuses
+    #[serde(default)]
+    #[serde(deserialize_with = "csv_vec_opt")]
+    pub status: Option<Vec<QueryStatusCodeSchema>>,
+    /// Only queries that have a label with this name
+    pub label: Option<String>,
+    /// Only queries requested at or after this time
+    pub requested_after: Option<DateTime<Utc>>,
+    /// Only queries requested before this time
+    pub requested_before: Option<DateTime<Utc>>,
+    /// Only queries that ended at or after this time
+    pub ended_after: Option<DateTime<Utc>>,
+    /// Only queries that ended before this time
+    pub ended_before: Option<DateTime<Utc>>,
+    /// Only queries that ran for at least this many seconds, queries that
+    /// have not ended yet are measured up to now
+    pub min_duration_secs: Option<i64>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -112,8 +130,9 @@ pub struct QueryCountParams {
     pub cluster_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "pyo3", pyclass(get_all))]
 #[cfg_attr(feature = "server", derive(ToSchema))]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct QueryCountSchema {
     pub timestamp: DateTime<Utc>,
     // signed to be able to deserialize from postgres
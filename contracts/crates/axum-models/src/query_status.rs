@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[cfg(feature = "pyo3")]
 use pyo3::pyclass;
 use serde::{Deserialize, Serialize};
@@ -15,3 +17,16 @@ pub enum QueryStatusCodeSchema {
     Failed,
     Canceled,
 }
+
+impl Display for QueryStatusCodeSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryStatusCodeSchema::Queued => write!(f, "Queued"),
+            QueryStatusCodeSchema::Scheduled => write!(f, "Scheduled"),
+            QueryStatusCodeSchema::InProgress => write!(f, "InProgress"),
+            QueryStatusCodeSchema::Success => write!(f, "Success"),
+            QueryStatusCodeSchema::Failed => write!(f, "Failed"),
+            QueryStatusCodeSchema::Canceled => write!(f, "Canceled"),
+        }
+    }
+}
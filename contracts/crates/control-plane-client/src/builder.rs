@@ -75,9 +75,12 @@ impl<'a> ApiRequestBuilder<'a> {
         T: Display,
         I: IntoIterator<Item = T>,
     {
-        let Some(values) = values else {
+        let Some(mut values) = values.map(|v| v.into_iter().peekable()) else {
             return self;
         };
+        if values.peek().is_none() {
+            return self;
+        }
         self.parameter_vec(name, values)
     }
 
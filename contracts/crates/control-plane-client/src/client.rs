@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::{BufMut, Bytes, BytesMut};
@@ -8,7 +9,7 @@ use polars_axum_models::*;
 #[cfg(feature = "pyo3")]
 use pyo3::pyclass;
 use reqwest::redirect;
-use reqwest_middleware::ClientBuilder;
+use reqwest_middleware::{ClientBuilder, Middleware};
 use uuid::Uuid;
 
 use crate::builder::ApiRequestBuilder;
@@ -41,6 +42,7 @@ impl Versions {
 pub struct ApiClientBuilder {
     builder: reqwest::ClientBuilder,
     retry_middleware: Option<RetryTransientMiddleware>,
+    middlewares: Vec<Arc<dyn Middleware>>,
 }
 
 impl Default for ApiClientBuilder {
@@ -52,6 +54,7 @@ impl Default for ApiClientBuilder {
                 .user_agent(user_agent(None))
                 .http2_keep_alive_timeout(Duration::from_secs(15)),
             retry_middleware: None,
+            middlewares: Vec::new(),
         }
     }
 }
@@ -92,11 +95,20 @@ impl ApiClientBuilder {
         self
     }
 
+    /// Add a middleware that runs after the retry middleware, so it sees every attempt.
+    pub fn with_middleware<M: Middleware>(mut self, middleware: M) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
     pub fn build(self, auth_header: String, address: String) -> ApiClient {
         let mut client_builder = ClientBuilder::new(self.builder.build().unwrap());
         if let Some(retry) = self.retry_middleware {
             client_builder = client_builder.with(retry);
         }
+        for middleware in self.middlewares {
+            client_builder = client_builder.with_arc(middleware);
+        }
 
         ApiClient {
             client: client_builder.build(),
@@ -731,6 +743,33 @@ impl ApiClient {
             .parameter("order_direction", "asc")
             .parameter_opt("cluster_id", filters.cluster_id)
             .parameter_opt("user_id", filters.user_id)
+            .parameter_vec_opt("status", filters.status)
+            .parameter_opt("label", filters.label)
+            .parameter_opt(
+                "requested_after",
+                filters
+                    .requested_after
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            )
+            .parameter_opt(
+                "requested_before",
+                filters
+                    .requested_before
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            )
+            .parameter_opt(
+                "ended_after",
+                filters
+                    .ended_after
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            )
+            .parameter_opt(
+                "ended_before",
+                filters
+                    .ended_before
+                    .map(|t| t.to_rfc3339_opts(SecondsFormat::Millis, true)),
+            )
+            .parameter_opt("min_duration_secs", filters.min_duration_secs)
             .await?
             .json()
             .await
@@ -769,8 +808,9 @@ impl ApiClient {
         window: &MetricWindow,
     ) -> Result<Vec<QueryCountSchema>> {
         let TimeWindow { start, end } = window.window;
-        // A custom limit as otherwise we get the default pagination which is 25
-        let limit = (end - start).num_days() + 1;
+        // A custom limit as otherwise we get the default pagination which is 25,
+        // one entry per bucket of `interval` in the window
+        let limit = (end - start).num_seconds() / window.interval.num_seconds().max(1) + 1;
 
         Ok(self
             .get(&format!("/api/v1/workspace/{workspace_id}/query/counts"))
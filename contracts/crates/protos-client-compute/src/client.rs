@@ -1,10 +1,14 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 use bytes::Bytes;
 use prost::Message;
 use prost_types::FieldMask;
-use protos_common::{QueryIdentifier, QueryPlans, QueryResult, map_trait};
+use protos_common::{
+    ACCEPTED_COMPRESSION_ENCODINGS, QueryIdentifier, QueryPlans, QueryResult, map_trait,
+};
 use serde::{Deserialize, Serialize};
+use tonic::codegen::tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 
 use crate::client::proto::QueryStageStatistics;
@@ -17,13 +21,30 @@ mod proto {
 
 pub use proto::client_service_client::ClientServiceClient;
 pub use proto::client_service_server::ClientServiceServer;
-pub use proto::{ClientService as ClientServiceProto, StageStatistics};
+pub use proto::{
+    ClientService as ClientServiceProto, StageStatistics,
+    SubmitQueryRequest as SubmitQueryRequestProto,
+};
 pub type QueryStatus = proto::QueryStatus;
 
+/// Server for `service` that accepts compressed requests and compresses the
+/// responses to clients that accept it.
+pub fn client_service_server<T: ClientServiceProto>(service: T) -> ClientServiceServer<T> {
+    ACCEPTED_COMPRESSION_ENCODINGS
+        .into_iter()
+        .fold(ClientServiceServer::new(service), |server, encoding| {
+            server.accept_compressed(encoding).send_compressed(encoding)
+        })
+}
+
 #[trait_variant::make(Send)]
 pub trait ClientService {
     type Error: Into<Status>;
 
+    type WatchQueryStream: Stream<Item = Result<QueryWatchEvent, Self::Error>> + Send + 'static;
+
+    type FetchResultStream: Stream<Item = Result<ResultChunk, Self::Error>> + Send + 'static;
+
     async fn submit_query(
         &self,
         request: Request<server::SubmitQueryRequest>,
@@ -48,6 +69,18 @@ pub trait ClientService {
         &self,
         request: Request<GetQueryPlansRequest>,
     ) -> Result<Response<QueryPlans>, Self::Error>;
+
+    /// Stream the status and stage progress of a query until it finishes.
+    async fn watch_query(
+        &self,
+        request: Request<QueryIdentifier>,
+    ) -> Result<Response<Self::WatchQueryStream>, Self::Error>;
+
+    /// Stream the result of a query as Arrow IPC chunks.
+    async fn fetch_result(
+        &self,
+        request: Request<FetchResultRequest>,
+    ) -> Result<Response<Self::FetchResultStream>, Self::Error>;
 }
 
 map_trait! {
@@ -58,6 +91,10 @@ map_trait! {
         get_query_plans(proto::GetQueryPlansRequest) -> proto::GetQueryPlansResponse;
         cancel_query(proto::CancelQueryRequest) -> proto::CancelQueryResponse;
     }
+    streams {
+        watch_query(proto::WatchQueryRequest) -> WatchQueryStream<proto::WatchQueryResponse>;
+        fetch_result(proto::FetchResultRequest) -> FetchResultStream<proto::FetchResultResponse>;
+    }
 }
 
 impl From<QueryIdentifier> for proto::SubmitQueryResponse {
@@ -126,6 +163,11 @@ impl From<QuerySettings> for proto::QuerySettings {
             preferred_graph_format: proto::GraphFormat::from(value.preferred_graph_format).into(),
             n_retries: value.n_retries,
             query_type: Some(value.query_type.into()),
+            timeout_ms: value
+                .timeout
+                .map(|timeout| timeout.as_millis().try_into().unwrap_or(u64::MAX)),
+            priority: proto::QueryPriority::from(value.priority).into(),
+            memory_limit_bytes: value.memory_limit,
         }
     }
 }
@@ -137,6 +179,9 @@ impl From<proto::QuerySettings> for QuerySettings {
             preferred_graph_format: value.preferred_graph_format().into(),
             n_retries: value.n_retries,
             query_type: value.query_type.unwrap().into(),
+            timeout: value.timeout_ms.map(Duration::from_millis),
+            priority: value.priority().into(),
+            memory_limit: value.memory_limit_bytes,
         }
     }
 }
@@ -341,12 +386,43 @@ impl From<proto::GraphFormat> for GraphFormat {
     }
 }
 
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryPriority {
+    #[default]
+    Batch,
+    /// Scheduled ahead of batch queries.
+    Interactive,
+}
+
+impl From<QueryPriority> for proto::QueryPriority {
+    fn from(value: QueryPriority) -> Self {
+        match value {
+            QueryPriority::Batch => Self::Batch,
+            QueryPriority::Interactive => Self::Interactive,
+        }
+    }
+}
+
+impl From<proto::QueryPriority> for QueryPriority {
+    fn from(value: proto::QueryPriority) -> Self {
+        match value {
+            proto::QueryPriority::Unspecified | proto::QueryPriority::Batch => Self::Batch,
+            proto::QueryPriority::Interactive => Self::Interactive,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct QuerySettings {
     pub engine: Engine,
     pub preferred_graph_format: GraphFormat,
     pub n_retries: u32,
     pub query_type: QueryType,
+    /// Wall-clock time after which the scheduler cancels the query.
+    pub timeout: Option<Duration>,
+    pub priority: QueryPriority,
+    /// Soft limit in bytes on the memory of the query, above which it spills to disk.
+    pub memory_limit: Option<u64>,
 }
 
 impl QuerySettings {
@@ -590,3 +666,171 @@ impl From<QueryPlans> for proto::GetQueryPlansResponse {
         Self { plans: Some(value) }
     }
 }
+
+impl From<proto::WatchQueryRequest> for QueryIdentifier {
+    fn from(proto::WatchQueryRequest { query_id }: proto::WatchQueryRequest) -> Self {
+        query_id.unwrap().into()
+    }
+}
+
+impl From<QueryIdentifier> for proto::WatchQueryRequest {
+    fn from(value: QueryIdentifier) -> Self {
+        Self {
+            query_id: Some(value.into()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StageProgress {
+    pub total_stages: u32,
+    pub finished_stages: u32,
+    pub failed_stages: u32,
+}
+
+/// A status transition or stage progress of a watched query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryWatchEvent {
+    pub status: QueryStatus,
+    pub progress: StageProgress,
+}
+
+impl From<proto::StageProgress> for StageProgress {
+    fn from(
+        proto::StageProgress {
+            total_stages,
+            finished_stages,
+            failed_stages,
+        }: proto::StageProgress,
+    ) -> Self {
+        Self {
+            total_stages,
+            finished_stages,
+            failed_stages,
+        }
+    }
+}
+
+impl From<StageProgress> for proto::StageProgress {
+    fn from(
+        StageProgress {
+            total_stages,
+            finished_stages,
+            failed_stages,
+        }: StageProgress,
+    ) -> Self {
+        Self {
+            total_stages,
+            finished_stages,
+            failed_stages,
+        }
+    }
+}
+
+impl From<proto::WatchQueryResponse> for QueryWatchEvent {
+    fn from(value: proto::WatchQueryResponse) -> Self {
+        let status = value.status();
+        let proto::WatchQueryResponse {
+            status: _,
+            progress,
+        } = value;
+        Self {
+            status,
+            progress: progress.unwrap_or_default().into(),
+        }
+    }
+}
+
+impl From<QueryWatchEvent> for proto::WatchQueryResponse {
+    fn from(QueryWatchEvent { status, progress }: QueryWatchEvent) -> Self {
+        Self {
+            status: status.into(),
+            progress: Some(progress.into()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FetchResultRequest {
+    pub query_id: QueryIdentifier,
+    /// Index of the first chunk to send.
+    pub start_chunk: u64,
+    pub max_chunk_bytes: Option<u64>,
+}
+
+/// A chunk of a query result, holding a complete Arrow IPC stream.
+#[derive(Debug, Clone)]
+pub struct ResultChunk {
+    pub index: u64,
+    pub total_chunks: u64,
+    pub num_rows: u64,
+    pub data: Bytes,
+}
+
+impl From<proto::FetchResultRequest> for FetchResultRequest {
+    fn from(
+        proto::FetchResultRequest {
+            query_id,
+            start_chunk,
+            max_chunk_bytes,
+        }: proto::FetchResultRequest,
+    ) -> Self {
+        Self {
+            query_id: query_id.unwrap().into(),
+            start_chunk,
+            max_chunk_bytes,
+        }
+    }
+}
+
+impl From<FetchResultRequest> for proto::FetchResultRequest {
+    fn from(
+        FetchResultRequest {
+            query_id,
+            start_chunk,
+            max_chunk_bytes,
+        }: FetchResultRequest,
+    ) -> Self {
+        Self {
+            query_id: Some(query_id.into()),
+            start_chunk,
+            max_chunk_bytes,
+        }
+    }
+}
+
+impl From<proto::FetchResultResponse> for ResultChunk {
+    fn from(
+        proto::FetchResultResponse {
+            chunk_index,
+            total_chunks,
+            num_rows,
+            data,
+        }: proto::FetchResultResponse,
+    ) -> Self {
+        Self {
+            index: chunk_index,
+            total_chunks,
+            num_rows,
+            data,
+        }
+    }
+}
+
+impl From<ResultChunk> for proto::FetchResultResponse {
+    fn from(
+        ResultChunk {
+            index,
+            total_chunks,
+            num_rows,
+            data,
+        }: ResultChunk,
+    ) -> Self {
+        Self {
+            chunk_index: index,
+            total_chunks,
+            num_rows,
+            data,
+        }
+    }
+}
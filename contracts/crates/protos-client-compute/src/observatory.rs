@@ -1,5 +1,5 @@
 use bytes::Bytes;
-use protos_common::QueryIdentifier;
+use protos_common::{ACCEPTED_COMPRESSION_ENCODINGS, QueryIdentifier};
 use tonic::transport::Channel;
 use tonic::{Request, Response, Status, async_trait};
 
@@ -11,6 +11,17 @@ mod proto {
 pub use proto::QueryProfileServiceClient;
 pub use proto::query_profile_service_server::QueryProfileServiceServer;
 
+/// Server for `service` that accepts compressed requests and compresses the
+/// responses to clients that accept it.
+pub fn query_profile_service_server<T: proto::QueryProfileService>(
+    service: T,
+) -> QueryProfileServiceServer<T> {
+    ACCEPTED_COMPRESSION_ENCODINGS.into_iter().fold(
+        QueryProfileServiceServer::new(service),
+        |server, encoding| server.accept_compressed(encoding).send_compressed(encoding),
+    )
+}
+
 #[derive(Clone)]
 pub struct QueryProfileClient {
     inner: proto::QueryProfileServiceClient<Channel>,
@@ -19,7 +30,10 @@ pub struct QueryProfileClient {
 impl QueryProfileClient {
     pub async fn new(channel: Channel) -> Result<Self, tonic::transport::Error> {
         Ok(Self {
-            inner: proto::QueryProfileServiceClient::new(channel),
+            inner: ACCEPTED_COMPRESSION_ENCODINGS.into_iter().fold(
+                proto::QueryProfileServiceClient::new(channel),
+                |client, encoding| client.accept_compressed(encoding),
+            ),
         })
     }
 
@@ -1,17 +1,35 @@
-use bytes::Bytes;
-use protos_common::{ComputeIdentifier, QueryIdentifier, QueryResult, map_trait};
+use bytes::{Bytes, BytesMut};
+use protos_common::macros::CollectLimit;
+use protos_common::{
+    ACCEPTED_COMPRESSION_ENCODINGS, ComputeIdentifier, QueryIdentifier, QueryResult, map_trait,
+};
+use sha2::{Digest, Sha256};
 use tonic::{Request, Response, Status};
 
+use crate::MAX_CHUNKED_PLAN_LENGTH;
+
 mod proto {
     pub use self::client_service_client::ClientServiceClient;
     pub use self::client_service_server::{ClientService, ClientServiceServer};
     pub use crate::proto::polars_cloud::control_plane::client::v1::*;
 }
 
-pub use proto::SubmitQueryRequest as SubmitQueryRequestProto;
+pub use proto::{
+    SubmitQueryChunk as SubmitQueryChunkProto, SubmitQueryRequest as SubmitQueryRequestProto,
+};
 pub type ClientServiceServer<T> = proto::ClientServiceServer<T>;
 pub type ClientServiceClient<T> = proto::ClientServiceClient<T>;
 
+/// Server for `service` that accepts compressed requests and compresses the
+/// responses to clients that accept it.
+pub fn client_service_server<T: proto::ClientService>(service: T) -> ClientServiceServer<T> {
+    ACCEPTED_COMPRESSION_ENCODINGS
+        .into_iter()
+        .fold(ClientServiceServer::new(service), |server, encoding| {
+            server.accept_compressed(encoding).send_compressed(encoding)
+        })
+}
+
 /// This is the trait downstream crates need to implement.
 #[trait_variant::make(Send)]
 pub trait ClientService {
@@ -32,6 +50,61 @@ map_trait! {
         submit_query(proto::SubmitQueryRequest) -> proto::SubmitQueryResponse;
         get_query_result(proto::GetQueryResultRequest) -> proto::GetQueryResultResponse;
     }
+    collects {
+        submit_query_chunked(proto::SubmitQueryChunk) -> proto::SubmitQueryResponse = submit_query;
+    }
+}
+
+fn check_plan_length(plan_length: u64) -> Result<(), Status> {
+    if plan_length > MAX_CHUNKED_PLAN_LENGTH {
+        return Err(Status::invalid_argument(format!(
+            "plan of {plan_length} bytes exceeds the maximum of {MAX_CHUNKED_PLAN_LENGTH} bytes"
+        )));
+    }
+    Ok(())
+}
+
+/// Plan bytes received so far, and the plan length announced by the header.
+#[derive(Default)]
+pub struct SubmitQueryChunkLimit {
+    plan_length: Option<u64>,
+    received: u64,
+}
+
+/// Stops collecting a chunked submission as soon as it sends more plan bytes
+/// than its header announced.
+impl CollectLimit for proto::SubmitQueryChunk {
+    type State = SubmitQueryChunkLimit;
+
+    fn check(state: &mut Self::State, message: &Self) -> Result<(), Status> {
+        use proto::submit_query_chunk::Part;
+
+        match (&message.part, state.plan_length) {
+            (Some(Part::Header(header)), None) => {
+                check_plan_length(header.plan_length)?;
+                state.plan_length = Some(header.plan_length);
+            },
+            (Some(Part::PlanChunk(chunk)), Some(plan_length)) => {
+                state.received += chunk.len() as u64;
+                if state.received > plan_length {
+                    return Err(Status::invalid_argument(format!(
+                        "received more than the {plan_length} plan bytes announced by the header"
+                    )));
+                }
+            },
+            (_, None) => {
+                return Err(Status::invalid_argument(
+                    "first message of a chunked submission must be the header",
+                ));
+            },
+            (_, Some(_)) => {
+                return Err(Status::invalid_argument(
+                    "only the first message of a chunked submission may be a header",
+                ));
+            },
+        }
+        Ok(())
+    }
 }
 
 impl From<QueryResult> for proto::GetQueryResultResponse {
@@ -97,6 +170,65 @@ pub mod server {
             }
         }
     }
+
+    /// Reassemble a chunked submission and verify the length and digest of the plan.
+    impl TryFrom<Vec<proto::SubmitQueryChunk>> for SubmitQueryRequest {
+        type Error = Status;
+
+        fn try_from(chunks: Vec<proto::SubmitQueryChunk>) -> Result<Self, Self::Error> {
+            use proto::submit_query_chunk::Part;
+
+            let mut chunks = chunks.into_iter().map(|chunk| chunk.part);
+            let Some(Some(Part::Header(header))) = chunks.next() else {
+                return Err(Status::invalid_argument(
+                    "first message of a chunked submission must be the header",
+                ));
+            };
+
+            check_plan_length(header.plan_length)?;
+            let mut plan = BytesMut::with_capacity(header.plan_length as usize);
+            for part in chunks {
+                match part {
+                    Some(Part::PlanChunk(chunk)) => {
+                        if (plan.len() + chunk.len()) as u64 > header.plan_length {
+                            return Err(Status::invalid_argument(format!(
+                                "received more than the {} plan bytes announced by the header",
+                                header.plan_length
+                            )));
+                        }
+                        plan.extend_from_slice(&chunk)
+                    },
+                    _ => {
+                        return Err(Status::invalid_argument(
+                            "only the first message of a chunked submission may be a header",
+                        ));
+                    },
+                }
+            }
+            if plan.len() as u64 != header.plan_length {
+                return Err(Status::invalid_argument(format!(
+                    "received {} plan bytes, expected {}",
+                    plan.len(),
+                    header.plan_length
+                )));
+            }
+            if Sha256::digest(&plan).as_slice() != header.plan_sha256 {
+                return Err(Status::data_loss("plan digest does not match"));
+            }
+
+            Ok(Self {
+                compute_id: header
+                    .compute_id
+                    .ok_or_else(|| Status::invalid_argument("missing compute id"))?
+                    .compute_id
+                    .parse()
+                    .map_err(|_| Status::invalid_argument("invalid compute id"))?,
+                settings: header.settings,
+                plan: plan.freeze(),
+                query_info: header.query_info.map(Into::into).unwrap_or_default(),
+            })
+        }
+    }
 }
 
 #[allow(clippy::module_inception)]
@@ -124,6 +256,34 @@ pub mod client {
             }
         }
     }
+
+    impl SubmitQueryRequest {
+        /// Split the request into a header and plan chunks of at most `chunk_length` bytes.
+        pub fn into_chunks(self, chunk_length: usize) -> Vec<proto::SubmitQueryChunk> {
+            use proto::submit_query_chunk::Part;
+
+            let header = proto::SubmitQueryHeader {
+                compute_id: Some(self.compute_id.into()),
+                settings: self.settings.encode(),
+                query_info: Some(self.query_info.into()),
+                plan_length: self.plan.len() as u64,
+                plan_sha256: Sha256::digest(&self.plan).to_vec().into(),
+            };
+
+            let mut chunks = Vec::with_capacity(1 + self.plan.len().div_ceil(chunk_length));
+            chunks.push(proto::SubmitQueryChunk {
+                part: Some(Part::Header(header)),
+            });
+            let mut plan = self.plan;
+            while !plan.is_empty() {
+                let chunk = plan.split_to(chunk_length.min(plan.len()));
+                chunks.push(proto::SubmitQueryChunk {
+                    part: Some(Part::PlanChunk(chunk)),
+                });
+            }
+            chunks
+        }
+    }
 }
 
 impl From<ComputeIdentifier> for proto::ComputeId {
@@ -133,3 +293,86 @@ impl From<ComputeIdentifier> for proto::ComputeId {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use protos_client_compute::client::QuerySettings;
+    use protos_common::QueryInfo;
+    use tonic::Code;
+
+    use super::*;
+
+    fn request(plan: &'static [u8]) -> client::SubmitQueryRequest {
+        client::SubmitQueryRequest {
+            compute_id: ComputeIdentifier::new(),
+            settings: QuerySettings::default(),
+            plan: Bytes::from_static(plan),
+            query_info: QueryInfo {
+                labels: vec!["label".to_string()],
+            },
+        }
+    }
+
+    fn header(chunks: &mut [proto::SubmitQueryChunk]) -> &mut proto::SubmitQueryHeader {
+        match &mut chunks[0].part {
+            Some(proto::submit_query_chunk::Part::Header(header)) => header,
+            _ => panic!("first chunk is not the header"),
+        }
+    }
+
+    #[test]
+    fn test_chunked_submission_round_trip() {
+        let request = request(b"a plan that is split in several chunks");
+        let compute_id = request.compute_id;
+        let chunks = request.clone().into_chunks(4);
+        assert_eq!(chunks.len(), 1 + request.plan.len().div_ceil(4));
+
+        let mut limit = SubmitQueryChunkLimit::default();
+        for chunk in &chunks {
+            proto::SubmitQueryChunk::check(&mut limit, chunk).unwrap();
+        }
+        let received = server::SubmitQueryRequest::try_from(chunks).unwrap();
+        assert_eq!(received.compute_id, compute_id);
+        assert_eq!(received.plan, request.plan);
+        assert_eq!(received.settings, request.settings.encode());
+        assert_eq!(received.query_info.labels, request.query_info.labels);
+    }
+
+    #[test]
+    fn test_chunked_submission_rejects_bad_digest() {
+        let mut chunks = request(b"plan").into_chunks(2);
+        header(&mut chunks).plan_sha256 = Bytes::from_static(&[0; 32]);
+        let err = server::SubmitQueryRequest::try_from(chunks).err().unwrap();
+        assert_eq!(err.code(), Code::DataLoss);
+    }
+
+    #[test]
+    fn test_chunked_submission_rejects_bad_length() {
+        let mut chunks = request(b"plan").into_chunks(2);
+        header(&mut chunks).plan_length = 3;
+        let mut limit = SubmitQueryChunkLimit::default();
+        let checked: Result<Vec<()>, Status> = chunks
+            .iter()
+            .map(|chunk| proto::SubmitQueryChunk::check(&mut limit, chunk))
+            .collect();
+        assert_eq!(checked.unwrap_err().code(), Code::InvalidArgument);
+        let err = server::SubmitQueryRequest::try_from(chunks).err().unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let mut chunks = request(b"plan").into_chunks(2);
+        header(&mut chunks).plan_length = 5;
+        let err = server::SubmitQueryRequest::try_from(chunks).err().unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_chunked_submission_rejects_oversized_plan() {
+        let mut chunks = request(b"plan").into_chunks(2);
+        header(&mut chunks).plan_length = u64::MAX;
+        let mut limit = SubmitQueryChunkLimit::default();
+        let err = proto::SubmitQueryChunk::check(&mut limit, &chunks[0]).unwrap_err();
+        assert_eq!(err.code(), Code::InvalidArgument);
+        let err = server::SubmitQueryRequest::try_from(chunks).err().unwrap();
+        assert_eq!(err.code(), Code::InvalidArgument);
+    }
+}
@@ -11,3 +11,9 @@ pub mod proto {
 // facilitate sending plans containing a small amount of data.
 // Make sure this matches the maximum message length set in the client / control plane.
 pub const MAX_MESSAGE_LENGTH_CONTROL_PLANE: usize = 10 * 1024 * 1024;
+
+/// Largest plan the control plane accepts in a chunked submission.
+pub const MAX_CHUNKED_PLAN_LENGTH: u64 = 1024 * 1024 * 1024;
+
+/// Size of the plan chunks of a query that exceeds [`MAX_MESSAGE_LENGTH_CONTROL_PLANE`].
+pub const PLAN_CHUNK_LENGTH: usize = 4 * 1024 * 1024;
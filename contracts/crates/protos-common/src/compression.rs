@@ -0,0 +1,115 @@
+use tonic::codec::CompressionEncoding;
+use tonic::{Code, Status};
+
+/// Compression encodings all clients and servers accept.
+pub const ACCEPTED_COMPRESSION_ENCODINGS: [CompressionEncoding; 2] =
+    [CompressionEncoding::Zstd, CompressionEncoding::Gzip];
+
+/// Messages smaller than this are not worth compressing.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// Compression of the messages a client sends.
+///
+/// tonic compresses either all or none of the messages of a client, so the
+/// threshold is applied per call by picking the encoding of the client.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Compression {
+    /// Encoding of large messages, `None` to never compress.
+    pub encoding: Option<CompressionEncoding>,
+    /// Messages with an encoded length below this are sent uncompressed.
+    pub threshold: usize,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Self {
+            encoding: None,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+impl Compression {
+    /// The encoding to send a message of `encoded_len` bytes with.
+    pub fn encoding_for(&self, encoded_len: usize) -> Option<CompressionEncoding> {
+        self.encoding.filter(|_| encoded_len >= self.threshold)
+    }
+
+    /// Fall back to an encoding the server accepts after it rejected a call.
+    ///
+    /// A server that does not accept the encoding of a message answers with
+    /// `Unimplemented` and lists the encodings it does accept in the
+    /// `grpc-accept-encoding` metadata. The first of those we support is used
+    /// from then on, or no compression at all. Returns whether the encoding
+    /// changed, in which case the call should be sent again.
+    pub fn renegotiate(&mut self, status: &Status) -> bool {
+        let Some(encoding) = self.encoding else {
+            return false;
+        };
+        if status.code() != Code::Unimplemented {
+            return false;
+        }
+        let Some(accepted) = status
+            .metadata()
+            .get("grpc-accept-encoding")
+            .and_then(|value| value.to_str().ok())
+        else {
+            return false;
+        };
+        let accepted = |candidate: &CompressionEncoding| {
+            accepted
+                .split(',')
+                .any(|name| Some(name.trim()) == encoding_name(*candidate))
+        };
+        if accepted(&encoding) {
+            return false;
+        }
+        self.encoding = ACCEPTED_COMPRESSION_ENCODINGS.into_iter().find(accepted);
+        true
+    }
+}
+
+fn encoding_name(encoding: CompressionEncoding) -> Option<&'static str> {
+    match encoding {
+        CompressionEncoding::Zstd => Some("zstd"),
+        CompressionEncoding::Gzip => Some("gzip"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejected(accepted: &'static str) -> Status {
+        let mut status =
+            Status::unimplemented("Content is compressed with `zstd` which isn't supported");
+        status
+            .metadata_mut()
+            .insert("grpc-accept-encoding", accepted.parse().unwrap());
+        status
+    }
+
+    #[test]
+    fn test_renegotiate() {
+        let zstd = Compression {
+            encoding: Some(CompressionEncoding::Zstd),
+            ..Default::default()
+        };
+
+        let mut compression = zstd;
+        assert!(compression.renegotiate(&rejected("gzip,deflate")));
+        assert_eq!(compression.encoding, Some(CompressionEncoding::Gzip));
+
+        let mut compression = zstd;
+        assert!(compression.renegotiate(&rejected("identity")));
+        assert_eq!(compression.encoding, None);
+        assert!(!compression.renegotiate(&rejected("identity")));
+
+        let mut compression = zstd;
+        assert!(!compression.renegotiate(&rejected("zstd, gzip")));
+        assert!(!compression.renegotiate(&Status::unimplemented("unknown method")));
+        assert!(!compression.renegotiate(&Status::internal("failed")));
+        assert_eq!(compression, zstd);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod compression;
 pub mod macros;
 
 pub use {prost, tonic};
@@ -17,6 +18,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 use std::str::FromStr;
 
+pub use compression::{ACCEPTED_COMPRESSION_ENCODINGS, Compression, DEFAULT_COMPRESSION_THRESHOLD};
 pub use identifier::{ComputeIdentifier, QueryIdentifier, TaskIdentifier};
 pub use prost::bytes::Bytes;
 pub use query_info::*;
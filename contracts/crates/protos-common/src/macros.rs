@@ -41,6 +41,104 @@ macro_rules! method {
     };
 }
 
+/// Adapts a server-streaming method, mapping every item of the returned stream.
+#[macro_export]
+macro_rules! stream_method {
+    ($fname:ident($request_ty:ty) -> $stream:ident<$item_ty:ty>) => {
+        type $stream = tonic::codegen::BoxStream<$item_ty>;
+
+        fn $fname<'life0, 'async_trait>(
+            &'life0 self,
+            request: tonic::Request<$request_ty>,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<
+                        Output = Result<tonic::Response<Self::$stream>, tonic::Status>,
+                    > + ::core::marker::Send
+                    + 'async_trait,
+            >,
+        >
+        where
+            'life0: 'async_trait,
+            Self: 'async_trait,
+        {
+            Box::pin(async move {
+                use tonic::codegen::tokio_stream::StreamExt;
+
+                let (metadata, extensions, body) = request.into_parts();
+                let body = body
+                    .try_into()
+                    .map_err($crate::macros::StatusOrInfallible::to_status)?;
+                match self
+                    .$fname(Request::from_parts(metadata, extensions, body))
+                    .await
+                {
+                    Ok(response) => Ok(response.map(|stream| {
+                        Box::pin(stream.map(|item| item.map(Into::into).map_err(Into::into)))
+                            as Self::$stream
+                    })),
+                    Err(e) => Err(e.into()),
+                }
+            })
+        }
+    };
+}
+
+/// Adapts a client-streaming method by collecting all messages and passing the
+/// request assembled from them to a unary method of the adapted trait.
+///
+/// Every message is checked with the [`CollectLimit`] of the message type before
+/// it is buffered, so a client cannot make the server buffer an unbounded stream.
+#[macro_export]
+macro_rules! collect_method {
+    ($fname:ident($message_ty:ty) -> $ret_ty:ty = $target:ident) => {
+        fn $fname<'life0, 'async_trait>(
+            &'life0 self,
+            request: tonic::Request<tonic::Streaming<$message_ty>>,
+        ) -> ::core::pin::Pin<
+            Box<
+                dyn ::core::future::Future<Output = Result<tonic::Response<$ret_ty>, tonic::Status>>
+                    + ::core::marker::Send
+                    + 'async_trait,
+            >,
+        >
+        where
+            'life0: 'async_trait,
+            Self: 'async_trait,
+        {
+            Box::pin(async move {
+                let (metadata, extensions, mut stream) = request.into_parts();
+                let mut messages = Vec::new();
+                let mut limit =
+                    <<$message_ty as $crate::macros::CollectLimit>::State as Default>::default();
+                while let Some(message) = stream.message().await? {
+                    <$message_ty as $crate::macros::CollectLimit>::check(&mut limit, &message)?;
+                    messages.push(message);
+                }
+                let body = messages
+                    .try_into()
+                    .map_err($crate::macros::StatusOrInfallible::to_status)?;
+                match self
+                    .$target(Request::from_parts(metadata, extensions, body))
+                    .await
+                {
+                    Ok(response) => Ok(response.map(Into::into)),
+                    Err(e) => Err(e.into()),
+                }
+            })
+        }
+    };
+}
+
+/// Bounds the messages of a client stream that the server collects.
+pub trait CollectLimit {
+    /// Tracks the messages received so far.
+    type State: Default;
+
+    /// Reject `message` if collecting it would exceed what the stream may send.
+    fn check(state: &mut Self::State, message: &Self) -> Result<(), Status>;
+}
+
 pub trait StatusOrInfallible {
     fn to_status(self) -> Status;
 }
@@ -61,10 +159,16 @@ impl StatusOrInfallible for Infallible {
 macro_rules! map_trait {
     (impl $adapted_trait:ident for $trait:ty {
         $($fname:ident($($arg:path)?) $(-> $ret:path)?;)+
-    }) => {
+    } $(streams {
+        $($sname:ident($sarg:path) -> $stream:ident<$sret:path>;)+
+    })? $(collects {
+        $($cname:ident($carg:path) -> $cret:path = $ctarget:ident;)+
+    })?) => {
         impl<T: $adapted_trait + Send + Sync + 'static> $trait for T
             where tonic::Status: From<T::Error>, {
             $($crate::method!{$fname($($arg)?) $( -> $ret)?})+
+            $($($crate::stream_method!{$sname($sarg) -> $stream<$sret>})+)?
+            $($($crate::collect_method!{$cname($carg) -> $cret = $ctarget})+)?
         }
     };
 }
@@ -63,6 +63,34 @@ impl From<common::FileType> for Option<FileType> {
     }
 }
 
+/// A limit of the query settings that a query can hit while running.
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub enum QueryLimit {
+    /// The query ran longer than its timeout and was canceled.
+    Timeout,
+    /// The query used more memory than its memory limit and spilled to disk.
+    Memory,
+}
+
+impl From<common::QueryLimit> for Option<QueryLimit> {
+    fn from(value: common::QueryLimit) -> Self {
+        Some(match value {
+            common::QueryLimit::Unspecified => return None,
+            common::QueryLimit::Timeout => QueryLimit::Timeout,
+            common::QueryLimit::Memory => QueryLimit::Memory,
+        })
+    }
+}
+
+impl From<QueryLimit> for common::QueryLimit {
+    fn from(value: QueryLimit) -> Self {
+        match value {
+            QueryLimit::Timeout => common::QueryLimit::Timeout,
+            QueryLimit::Memory => common::QueryLimit::Memory,
+        }
+    }
+}
+
 impl From<Option<FileType>> for common::FileType {
     fn from(value: Option<FileType>) -> Self {
         let Some(filetype) = value else {
@@ -84,6 +112,7 @@ pub struct QueryResult {
     pub failed_stages: u32,
     pub errors: Vec<String>,
     pub output: Option<QueryOutput>,
+    pub limits_hit: Vec<QueryLimit>,
 }
 
 #[derive(Debug)]
@@ -135,6 +164,11 @@ impl From<QueryOutput> for common::QueryOutput {
 
 impl From<common::QueryResult> for QueryResult {
     fn from(value: common::QueryResult) -> Self {
+        // Limits unknown to this client are skipped
+        let limits_hit = value
+            .limits_hit()
+            .filter_map(Option::<QueryLimit>::from)
+            .collect();
         Self {
             total_stages: value.total_stages,
             finished_stages: value.finished_stages,
@@ -145,6 +179,7 @@ impl From<common::QueryResult> for QueryResult {
                 .map(|error| error.message)
                 .collect(),
             output: value.output.map(Into::into),
+            limits_hit,
         }
     }
 }
@@ -161,6 +196,11 @@ impl From<QueryResult> for common::QueryResult {
                 .map(|message| common::Error { message })
                 .collect(),
             output: value.output.map(Into::into),
+            limits_hit: value
+                .limits_hit
+                .into_iter()
+                .map(|limit| common::QueryLimit::from(limit).into())
+                .collect(),
         }
     }
 }